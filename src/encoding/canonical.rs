@@ -0,0 +1,147 @@
+use encoding::{EncodeOptions, NonFinite};
+use types::{Value};
+
+/// The options canonical output is written with, whatever else was asked for.
+pub(crate) fn options() -> EncodeOptions {
+    EncodeOptions {
+        ascii_only: false,
+        non_finite: NonFinite::Error,
+        pretty:     None,
+        canonical:  true,
+    }
+}
+
+/// Orders entries by the UTF-16 code units of their keys, which differs from Rust's byte order
+/// for characters beyond U+FFFF.
+pub(crate) fn sort_entries(entries: &mut [(&String,&Value)]) {
+    entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+}
+
+/// Formats a finite number the way ECMAScript's `Number.prototype.toString` does: the shortest
+/// digits that round-trip, written out in full between 1e-7 and 1e21 and with an exponent
+/// outside that range.
+pub(crate) fn format_number(n: f64) -> String {
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    // `{:e}` finds how many digits the shortest round-trip form needs. When the value lies exactly
+    // halfway between two such forms it rounds up, but ECMAScript wants the even one, so the
+    // digits come from formatting again at that precision, which rounds half to even.
+    let shortest: String = format!("{:e}", n.abs());
+    let k: i32 = shortest.find('e').unwrap() as i32 - if shortest.contains('.') { 1 } else { 0 };
+    let exponential: String = format!("{:.*e}", k as usize - 1, n.abs());
+    let (mantissa, exponent): (&str, &str) = exponential.split_at(exponential.find('e').unwrap());
+    let digits: String = mantissa.replace('.', "");
+    let e: i32 = exponent[1..].parse::<i32>().unwrap() + 1;
+
+    let mut result: String = String::new();
+    if n < 0.0 {
+        result.push('-');
+    }
+    if k <= e && e <= 21 {
+        result.push_str(&digits);
+        result.extend(::std::iter::repeat_n('0', (e - k) as usize));
+    } else if 0 < e && e <= 21 {
+        result.push_str(&digits[..e as usize]);
+        result.push('.');
+        result.push_str(&digits[e as usize..]);
+    } else if -6 < e && e <= 0 {
+        result.push_str("0.");
+        result.extend(::std::iter::repeat_n('0', -e as usize));
+        result.push_str(&digits);
+    } else {
+        result.push_str(&digits[..1]);
+        if k > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        result.push_str(&format!("e{}{}", if e > 0 { "+" } else { "-" }, (e - 1).abs()));
+    }
+    result
+}
+
+#[cfg(test)]
+use encoding::{EncodeError, ToJson};
+
+#[test]
+fn numbers_match_the_rfc_8785_vectors() {
+    let vectors: [(u64, &str); 24] = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+    for &(bits, expected) in vectors.iter() {
+        assert_eq!( format_number(f64::from_bits(bits)), expected, "{:016x}", bits );
+    }
+}
+
+#[test]
+fn documents_match_the_rfc_8785_example() {
+    let json: &str = "{\n  \"numbers\": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],\n  \
+                      \"string\": \"\\u20ac$\\u000F\\u000aA'\\u0042\\u0022\\u005c\\\\\\\"\\/\",\n  \
+                      \"literals\": [null, true, false]\n}";
+    let canonical: &str = "{\"literals\":[null,true,false],\"numbers\":[333333333.3333333,1e+30,4.5,0.002,1e-27],\
+                           \"string\":\"€$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}";
+    assert_eq!( ::parsing::parse(json).unwrap().to_canonical_json().unwrap(), canonical );
+}
+
+#[test]
+fn keys_sort_by_utf16_code_units() {
+    let json: &str = "{\"\\u20ac\":\"Euro Sign\",\"\\r\":\"Carriage Return\",\"\\ufb33\":\"Hebrew Letter Dalet With Dagesh\",\
+                      \"1\":\"One\",\"\\ud83d\\ude00\":\"Emoji: Grinning Face\",\"\\u0080\":\"Control\",\
+                      \"\\u00f6\":\"Latin Small Letter O With Diaeresis\"}";
+    let value: Value = ::parsing::parse(json).unwrap();
+    let keys: Vec<String> = match ::parsing::parse(&value.to_canonical_json().unwrap()).unwrap() {
+        Value::Object(object) => object.keys().cloned().collect(),
+        other                 => panic!("expected an object, got {:?}", other),
+    };
+    assert_eq!( keys, vec!["\r", "1", "\u{80}", "ö", "€", "😀", "\u{fb33}"] );
+}
+
+#[test]
+fn canonical_output_ignores_layout_options() {
+    let value: Value = ::parsing::parse("{\"b\":[1,\"é\"],\"a\":9007199254740993}").unwrap();
+    let options: EncodeOptions = EncodeOptions {
+        ascii_only: true,
+        pretty:     Some(::encoding::PrettyOptions::default()),
+        canonical:  true,
+        ..EncodeOptions::default()
+    };
+    assert_eq!( value.to_json_with(&options).unwrap(), "{\"a\":9007199254740992,\"b\":[1,\"é\"]}" );
+    let options: EncodeOptions = EncodeOptions { non_finite: NonFinite::Null, canonical: true, ..EncodeOptions::default() };
+    assert!( matches!(Value::Number(::types::Number::from(f64::NAN)).to_json_with(&options), Err(EncodeError::NonFiniteNumber(_))) );
+}
+
+#[test]
+fn numbers_beyond_f64_cannot_be_canonical() {
+    let decimal: ::parsing::ParseOptions = ::parsing::ParseOptions { decimal_numbers: true, ..Default::default() };
+    let value: Value = ::parsing::parse_with_options("[1e400]", &decimal).unwrap();
+    assert!( matches!(value.to_canonical_json(), Err(EncodeError::NonFiniteNumber(n)) if n == f64::INFINITY) );
+
+    let arbitrary: ::parsing::ParseOptions = ::parsing::ParseOptions { arbitrary_precision: true, ..Default::default() };
+    let value: Value = ::parsing::parse_with_options(&format!("-{}", "9".repeat(400)), &arbitrary).unwrap();
+    assert!( matches!(value.to_canonical_json(), Err(EncodeError::NonFiniteNumber(n)) if n == f64::NEG_INFINITY) );
+
+    assert!( matches!(::parsing::parse("1e400").unwrap().to_canonical_json(), Err(EncodeError::NonFiniteNumber(_))) );
+}
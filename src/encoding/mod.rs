@@ -1,47 +1,146 @@
+use std::error::{Error};
+use std::fmt;
+use std::io;
 use types::{Value, Array, Object};
 
-trait ToJson {
-    fn to_json(&self) -> String;
+pub mod ndjson;
+mod canonical;
+mod pretty;
+mod writer;
+
+use self::writer::{IoAdapter, Writer};
+
+pub use self::pretty::{Indent, Newline, PrettyOptions};
+
+/// What to do with a NaN or infinite number, which JSON has no way to write.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NonFinite {
+    /// Fail with `EncodeError::NonFiniteNumber`.
+    #[default]
+    Error,
+    /// Write `null` in its place.
+    Null,
+    /// Write it as one of the strings `"NaN"`, `"Infinity"` or `"-Infinity"`.
+    String,
 }
 
-impl ToJson for Value {
-    fn to_json(&self) -> String {
+/// Knobs for how values are written out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EncodeOptions {
+    /// Escape every non-ASCII character as `\uXXXX` (astral characters as a surrogate pair) so
+    /// the output is pure ASCII.
+    pub ascii_only: bool,
+    pub non_finite: NonFinite,
+    /// Lay the output out over indented lines instead of writing it compactly.
+    pub pretty:     Option<PrettyOptions>,
+    /// Write the RFC 8785 canonical form: no whitespace, keys sorted by their UTF-16 code units
+    /// and numbers formatted as ECMAScript does. `ascii_only` and `pretty` are ignored and NaN or
+    /// infinite numbers always fail.
+    pub canonical:  bool,
+}
+
+/// Everything that can go wrong while writing JSON.
+#[derive(Debug)]
+pub enum EncodeError {
+    NonFiniteNumber(f64),
+    /// A `fmt::Write` sink refused the output.
+    Format,
+    /// An `io::Write` sink failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Value::Array(ref x)  => format!("{}",x.to_json()),
-            Value::Boolean(x)    => format!("{}",x),
-            Value::None          => "null".to_string(),
-            Value::Number(x)     => format!("{}",x),
-            Value::Object(ref x) => format!("{}",x.to_json()),
-            Value::String(ref x) => format!("\"{}\"",x),
+            EncodeError::NonFiniteNumber(n) => write!(f, "{} cannot be represented in JSON", n),
+            EncodeError::Format             => write!(f, "the output could not be written"),
+            EncodeError::Io(ref error)      => write!(f, "the output could not be written: {}", error),
         }
     }
 }
 
-impl ToJson for Array {
+impl Error for EncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            EncodeError::Io(ref error) => Some(error),
+            _                          => None,
+        }
+    }
+}
+
+impl From<fmt::Error> for EncodeError {
+    fn from(_: fmt::Error) -> EncodeError {
+        EncodeError::Format
+    }
+}
+
+/// Types that can be written out as JSON text.
+pub trait ToJson {
+    /// Writes the JSON text into `out` piece by piece, without building the document in memory.
+    fn write_json<W: fmt::Write>(&self, out: &mut W, options: &EncodeOptions) -> Result<(),EncodeError>;
+
+    fn to_json_with(&self, options: &EncodeOptions) -> Result<String,EncodeError> {
+        let mut out: String = String::new();
+        self.write_json(&mut out, options)?;
+        Ok(out)
+    }
+
+    /// Streams the JSON text into `writer`. It arrives in many small writes, so files and sockets
+    /// should be wrapped in a `BufWriter`.
+    fn to_writer<W: io::Write>(&self, writer: W, options: &EncodeOptions) -> Result<(),EncodeError> {
+        let mut adapter: IoAdapter<W> = IoAdapter::new(writer);
+        match self.write_json(&mut adapter, options) {
+            Err(EncodeError::Format) if adapter.error.is_some() => {
+                Err(EncodeError::Io(adapter.error.take().unwrap()))
+            },
+            result => result,
+        }
+    }
+
+    /// Writes compact JSON with the default options, except that NaN and infinite numbers are
+    /// written as `null` the way JavaScript's `JSON.stringify` does, so this never fails.
     fn to_json(&self) -> String {
-        let mut result: String = String::new();
-        result.push_str("[");
-        result.push_str( &self
-                         .iter()
-                         .map(|value| value.to_json()).collect::<Vec<String>>().join(",") );
-        result.push_str("]");
-        result
+        let options: EncodeOptions = EncodeOptions { non_finite: NonFinite::Null, ..EncodeOptions::default() };
+        self.to_json_with(&options).expect("encoding with NonFinite::Null cannot fail")
+    }
+
+    /// The RFC 8785 canonical form, identical for equal values, for hashing or signing.
+    fn to_canonical_json(&self) -> Result<String,EncodeError> {
+        self.to_json_with(&EncodeOptions { canonical: true, ..EncodeOptions::default() })
+    }
+
+    /// Like `to_json`, but indented with the default `PrettyOptions`.
+    fn to_pretty_json(&self) -> String {
+        let options: EncodeOptions = EncodeOptions {
+            non_finite: NonFinite::Null,
+            pretty:     Some(PrettyOptions::default()),
+            ..EncodeOptions::default()
+        };
+        self.to_json_with(&options).expect("encoding with NonFinite::Null cannot fail")
+    }
+}
+
+impl ToJson for Value {
+    fn write_json<W: fmt::Write>(&self, out: &mut W, options: &EncodeOptions) -> Result<(),EncodeError> {
+        Writer::new(out, options).write_value(self, 0)
+    }
+}
+
+impl ToJson for Array {
+    fn write_json<W: fmt::Write>(&self, out: &mut W, options: &EncodeOptions) -> Result<(),EncodeError> {
+        Writer::new(out, options).write_array(self, 0)
     }
 }
 
 impl ToJson for Object {
-    fn to_json(&self) -> String {
-        let mut result: String = String::new();
-        result.push_str("{");
-        result.push_str( &self
-                         .iter()
-                         .map(|(key,value)| format!("{}:{}", Value::String(key.clone()).to_json(), value.to_json()))
-                         .collect::<Vec<String>>().join(",") );
-        result.push_str("}");
-        result
+    fn write_json<W: fmt::Write>(&self, out: &mut W, options: &EncodeOptions) -> Result<(),EncodeError> {
+        Writer::new(out, options).write_object(self, 0)
     }
 }
 
+#[cfg(test)]
+use types::{Number};
+
 #[test]
 fn empty_object_check() {
     let test_object: Object = Object::new();
@@ -57,7 +156,7 @@ fn simple_object_check() {
 
 #[test]
 fn array_of_numbers_check() {
-    let test_array: Array = vec![Value::Number(1f64),Value::Number(2f64)];
+    let test_array: Array = vec![Value::Number(::types::Number::from(1f64)),Value::Number(::types::Number::from(2f64))];
     assert_eq!(&test_array.to_json(),"[1,2]");
 }
 
@@ -84,3 +183,105 @@ fn array_of_empty_objects_check() {
     let test_array: Array = vec![Value::Object(Object::new()),Value::Object(Object::new())];
     assert_eq!(&test_array.to_json(),"[{},{}]");
 }
+
+#[test]
+fn strings_are_escaped() {
+    assert_eq!(&Value::String("a\"b\\c".to_string()).to_json(),"\"a\\\"b\\\\c\"");
+    assert_eq!(&Value::String("\n\r\t\u{08}\u{0c}".to_string()).to_json(),"\"\\n\\r\\t\\b\\f\"");
+    assert_eq!(&Value::String("\u{00}\u{1f}".to_string()).to_json(),"\"\\u0000\\u001f\"");
+    assert_eq!(&Value::String("/é😀".to_string()).to_json(),"\"/é😀\"");
+}
+
+#[test]
+fn object_keys_are_escaped() {
+    let mut test_object: Object = Object::new();
+    test_object.insert("\"key\"".to_string(),Value::None);
+    assert_eq!(&test_object.to_json(),"{\"\\\"key\\\"\":null}");
+}
+
+#[test]
+fn ascii_only_strings_escape_non_ascii() {
+    let options: EncodeOptions = EncodeOptions { ascii_only: true, ..EncodeOptions::default() };
+    assert_eq!(&Value::String("aé€😀".to_string()).to_json_with(&options).unwrap(),"\"a\\u00e9\\u20ac\\ud83d\\ude00\"");
+}
+
+#[test]
+fn escaped_strings_round_trip() {
+    use parsing::{FromJson};
+    let strings: [&str; 5] = ["plain", "\"quoted\" \\ back\\slash", "\u{00}\u{01}\n\u{1f}\u{7f}", "é€", "😀 \u{10ffff}"];
+    for string in strings.iter() {
+        let value: Value = Value::String(string.to_string());
+        assert_eq!(&*String::from_json(&value.to_json()).unwrap(), string);
+        assert_eq!(&*String::from_json(&value.to_json_with(&EncodeOptions { ascii_only: true, ..EncodeOptions::default() }).unwrap()).unwrap(), string);
+    }
+}
+
+#[test]
+fn large_integers_round_trip_exactly() {
+    let json: &str = "[9007199254740993,18446744073709551615,-9223372036854775808,0.5]";
+    assert_eq!(&::parsing::parse(json).unwrap().to_json(),json);
+
+    let huge: &str = "[123456789012345678901234567890]";
+    let options: ::parsing::ParseOptions = ::parsing::ParseOptions { arbitrary_precision: true, ..Default::default() };
+    assert_eq!(&::parsing::parse_with_options(huge,&options).unwrap().to_json(),huge);
+}
+
+#[test]
+fn decimals_are_written_verbatim() {
+    let json: &str = "[0.10,-1.2500E+3,{\"price\":7.00}]";
+    let options: ::parsing::ParseOptions = ::parsing::ParseOptions { decimal_numbers: true, ..Default::default() };
+    assert_eq!(&::parsing::parse_with_options(json,&options).unwrap().to_json(),json);
+}
+
+#[test]
+fn non_finite_numbers_follow_the_policy() {
+    let test_array: Array = vec![Value::Number(Number::from(1.5)),Value::Number(Number::from(f64::NAN)),
+                                 Value::Number(Number::from(f64::INFINITY)),Value::Number(Number::from(f64::NEG_INFINITY))];
+    let options = |non_finite: NonFinite| EncodeOptions { non_finite, ..EncodeOptions::default() };
+    assert!(matches!(test_array[2].to_json_with(&options(NonFinite::Error)),Err(EncodeError::NonFiniteNumber(n)) if n == f64::INFINITY));
+    assert!(test_array.to_json_with(&options(NonFinite::Error)).is_err());
+    assert!(Value::Number(Number::from(f64::NAN)).to_json_with(&EncodeOptions::default()).is_err());
+    assert_eq!(&test_array.to_json_with(&options(NonFinite::Null)).unwrap(),"[1.5,null,null,null]");
+    assert_eq!(&test_array.to_json_with(&options(NonFinite::String)).unwrap(),"[1.5,\"NaN\",\"Infinity\",\"-Infinity\"]");
+    assert_eq!(&test_array.to_json(),"[1.5,null,null,null]");
+}
+
+#[test]
+fn objects_are_written_in_document_order() {
+    let json: &str = "{\"z\":1,\"a\":{\"y\":2,\"b\":3},\"m\":[{\"x\":4,\"c\":5}]}";
+    assert_eq!(&::parsing::parse(json).unwrap().to_json(),json);
+}
+
+#[test]
+fn values_stream_into_writers() {
+    let value: Value = ::parsing::parse("{\"a\":[1,\"é\\n\",{\"b\":null}],\"c\":true}").unwrap();
+    let options: EncodeOptions = EncodeOptions { pretty: Some(PrettyOptions::default()), ..EncodeOptions::default() };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    value.to_writer(&mut bytes, &options).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(),value.to_pretty_json());
+
+    let mut text: String = "json: ".to_string();
+    value.write_json(&mut text, &EncodeOptions::default()).unwrap();
+    assert_eq!(text,format!("json: {}",value.to_json()));
+}
+
+#[test]
+fn writer_failures_are_reported() {
+    struct Full;
+    impl io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let test_array: Array = vec![Value::Boolean(true)];
+    match test_array.to_writer(Full, &EncodeOptions::default()) {
+        Err(EncodeError::Io(error)) => assert_eq!(error.kind(),io::ErrorKind::StorageFull),
+        other                       => panic!("expected an I/O error, got {:?}", other),
+    }
+    let non_finite: Array = vec![Value::Number(Number::from(f64::NAN))];
+    assert!(matches!(non_finite.to_writer(Vec::new(), &EncodeOptions::default()),Err(EncodeError::NonFiniteNumber(_))));
+}
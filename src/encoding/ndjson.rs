@@ -0,0 +1,67 @@
+use std::io;
+use encoding::{EncodeError, EncodeOptions, NonFinite, ToJson};
+
+/// Writes newline-delimited JSON (NDJSON, also known as JSON Lines): each value compactly on a
+/// line of its own.
+pub struct NdjsonWriter<W: io::Write> {
+    writer:  W,
+    options: EncodeOptions,
+}
+
+impl<W: io::Write> NdjsonWriter<W> {
+    /// Writes values as `to_json` does, with NaN and infinite numbers as `null`.
+    pub fn new(writer: W) -> NdjsonWriter<W> {
+        NdjsonWriter::with_options(writer, EncodeOptions { non_finite: NonFinite::Null, ..EncodeOptions::default() })
+    }
+    /// Writes values with `options`, except that `pretty` is ignored since every value must stay
+    /// on one line.
+    pub fn with_options(writer: W, options: EncodeOptions) -> NdjsonWriter<W> {
+        NdjsonWriter {
+            writer,
+            options: EncodeOptions { pretty: None, ..options },
+        }
+    }
+
+    /// Writes `value` followed by a newline.
+    pub fn write<T: ToJson>(&mut self, value: &T) -> Result<(),EncodeError> {
+        value.to_writer(&mut self.writer, &self.options)?;
+        self.writer.write_all(b"\n").map_err(EncodeError::Io)
+    }
+
+    pub fn flush(&mut self) -> Result<(),EncodeError> {
+        self.writer.flush().map_err(EncodeError::Io)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+use types::{Array, Number, Value};
+
+#[test]
+fn values_are_written_one_per_line() {
+    let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new());
+    let pretty: EncodeOptions = EncodeOptions { pretty: Some(::encoding::PrettyOptions::default()), ..EncodeOptions::default() };
+    writer.write(&::parsing::parse("{\"a\": [1, 2]}").unwrap()).unwrap();
+    writer.write(&Value::Number(Number::from(f64::NAN))).unwrap();
+    writer.write(&Array::new()).unwrap();
+    assert_eq!( String::from_utf8(writer.into_inner()).unwrap(), "{\"a\":[1,2]}\nnull\n[]\n" );
+
+    let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::with_options(Vec::new(), pretty);
+    writer.write(&::parsing::parse("{\"a\": [1, 2]}").unwrap()).unwrap();
+    assert_eq!( String::from_utf8(writer.into_inner()).unwrap(), "{\"a\":[1,2]}\n" );
+}
+
+#[test]
+fn written_lines_read_back() {
+    let values: Vec<Value> = vec![::parsing::parse("{\"s\":\"line\\nbreak\"}").unwrap(), Value::Boolean(false)];
+    let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new());
+    for value in values.iter() {
+        writer.write(value).unwrap();
+    }
+    let bytes: Vec<u8> = writer.into_inner();
+    let read: Vec<Value> = ::parsing::ndjson::NdjsonReader::new(&bytes[..]).map(Result::unwrap).collect();
+    assert_eq!( read, values );
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+/// What one level of indentation is made of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Indent {
+    Spaces(usize),
+    Tab,
+}
+
+/// The line ending written between lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Newline {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+/// Layout for human-readable output: every array element and object entry on its own line,
+/// indented by its depth.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrettyOptions {
+    pub indent:            Indent,
+    pub newline:           Newline,
+    /// Write `"key": value` rather than `"key":value`.
+    pub space_after_colon: bool,
+    /// Keep an array of numbers, strings, booleans and nulls on one line, as `[1, 2, 3]`, when
+    /// the array itself, from `[` to `]`, is at most this many characters long. The indentation
+    /// and any `"key": ` before it are not counted.
+    pub inline_array_width: Option<usize>,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> PrettyOptions {
+        PrettyOptions {
+            indent:             Indent::Spaces(2),
+            newline:            Newline::Lf,
+            space_after_colon:  true,
+            inline_array_width: None,
+        }
+    }
+}
+
+impl PrettyOptions {
+    pub(crate) fn write_newline<W: fmt::Write>(&self, out: &mut W, depth: usize) -> fmt::Result {
+        out.write_str(match self.newline {
+            Newline::Lf   => "\n",
+            Newline::CrLf => "\r\n",
+        })?;
+        for _ in 0..depth {
+            match self.indent {
+                Indent::Spaces(n) => write!(out, "{:1$}", "", n)?,
+                Indent::Tab       => out.write_char('\t')?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use encoding::{EncodeOptions, ToJson};
+#[cfg(test)]
+use types::{Value};
+
+#[test]
+fn values_are_indented_by_depth() {
+    let value: Value = ::parsing::parse("{\"name\":\"James\",\"tags\":[1,{\"a\":null}],\"empty\":{},\"none\":[]}").unwrap();
+    assert_eq!(value.to_pretty_json(),
+               "{\n  \"name\": \"James\",\n  \"tags\": [\n    1,\n    {\n      \"a\": null\n    }\n  ],\n  \"empty\": {},\n  \"none\": []\n}");
+}
+
+#[test]
+fn layout_is_configurable() {
+    let value: Value = ::parsing::parse("{\"a\":[1,2],\"b\":true}").unwrap();
+    let pretty: PrettyOptions = PrettyOptions {
+        indent:            Indent::Tab,
+        newline:           Newline::CrLf,
+        space_after_colon: false,
+        ..PrettyOptions::default()
+    };
+    let options: EncodeOptions = EncodeOptions { pretty: Some(pretty), ..EncodeOptions::default() };
+    assert_eq!(value.to_json_with(&options).unwrap(), "{\r\n\t\"a\":[\r\n\t\t1,\r\n\t\t2\r\n\t],\r\n\t\"b\":true\r\n}");
+}
+
+#[test]
+fn short_arrays_of_scalars_stay_on_one_line() {
+    let value: Value = ::parsing::parse("[[1,2,3],[\"abcdefgh\",null],[[1]]]").unwrap();
+    let pretty: PrettyOptions = PrettyOptions { indent: Indent::Spaces(4), inline_array_width: Some(10), ..PrettyOptions::default() };
+    let options: EncodeOptions = EncodeOptions { pretty: Some(pretty), ..EncodeOptions::default() };
+    assert_eq!(value.to_json_with(&options).unwrap(),
+               "[\n    [1, 2, 3],\n    [\n        \"abcdefgh\",\n        null\n    ],\n    [\n        [1]\n    ]\n]");
+}
+
+#[test]
+fn pretty_output_parses_back() {
+    let json: &str = "{\"z\":[1.5,\"\\n\",{\"y\":[]}],\"a\":{\"b\":{\"c\":false}}}";
+    let value: Value = ::parsing::parse(json).unwrap();
+    assert_eq!(::parsing::parse(&value.to_pretty_json()).unwrap(), value);
+    assert_eq!(value.to_json(), json);
+}
@@ -0,0 +1,232 @@
+use std::fmt::{self, Write};
+use std::io;
+use encoding::{canonical, EncodeError, EncodeOptions, NonFinite};
+use types::{Value, Number, Object};
+
+/// Writes values straight into a sink as it walks them, keeping track of how deeply nested it is
+/// for pretty output. Nothing is buffered except an array being tried on one line, and that only
+/// up to the width allowed for it.
+pub(crate) struct Writer<W: Write> {
+    options: EncodeOptions,
+    out:     W,
+}
+
+impl<W: Write> Writer<W> {
+    pub(crate) fn new(out: W, options: &EncodeOptions) -> Writer<W> {
+        let options: EncodeOptions = if options.canonical {
+            canonical::options()
+        } else {
+            *options
+        };
+        Writer { options, out }
+    }
+
+    pub(crate) fn write_value(&mut self, value: &Value, depth: usize) -> Result<(),EncodeError> {
+        match *value {
+            Value::Array(ref x)  => self.write_array(x, depth)?,
+            Value::Boolean(x)    => self.out.write_str(if x { "true" } else { "false" })?,
+            Value::None          => self.out.write_str("null")?,
+            Value::Number(ref x) => self.write_number(x)?,
+            Value::Object(ref x) => self.write_object(x, depth)?,
+            Value::String(ref x) => escape_string(&mut self.out, x, &self.options)?,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_array(&mut self, array: &[Value], depth: usize) -> Result<(),EncodeError> {
+        if let Some(width) = self.options.pretty.and_then(|pretty| pretty.inline_array_width) {
+            if let Some(line) = self.inline_array(array, width)? {
+                self.out.write_str(&line)?;
+                return Ok(());
+            }
+        }
+        self.out.write_char('[')?;
+        for (i, value) in array.iter().enumerate() {
+            if i > 0 {
+                self.out.write_char(',')?;
+            }
+            self.write_newline(depth + 1)?;
+            self.write_value(value, depth + 1)?;
+        }
+        if !array.is_empty() {
+            self.write_newline(depth)?;
+        }
+        self.out.write_char(']')?;
+        Ok(())
+    }
+
+    /// An array of scalars written on one line, unless it is empty or that text alone would be
+    /// wider than `width`. The line is given up as soon as it grows too wide, so a long array is
+    /// never held in full.
+    fn inline_array(&self, array: &[Value], width: usize) -> Result<Option<String>,EncodeError> {
+        let scalars: bool = array.iter().all(|value| !matches!(*value, Value::Array(_) | Value::Object(_)));
+        if array.is_empty() || !scalars {
+            return Ok(None);
+        }
+        let mut writer: Writer<Bounded> = Writer::new(Bounded::new(width), &self.options);
+        match writer.write_inline(array) {
+            Ok(())                         => Ok(Some(writer.out.line)),
+            Err(_) if writer.out.is_full() => Ok(None),
+            Err(error)                     => Err(error),
+        }
+    }
+
+    fn write_inline(&mut self, array: &[Value]) -> Result<(),EncodeError> {
+        self.out.write_char('[')?;
+        for (i, value) in array.iter().enumerate() {
+            if i > 0 {
+                self.out.write_str(", ")?;
+            }
+            self.write_value(value, 0)?;
+        }
+        self.out.write_char(']')?;
+        Ok(())
+    }
+
+    pub(crate) fn write_object(&mut self, object: &Object, depth: usize) -> Result<(),EncodeError> {
+        let colon: &str = match self.options.pretty {
+            Some(pretty) if pretty.space_after_colon => ": ",
+            _                                        => ":",
+        };
+        let mut entries: Vec<(&String,&Value)> = object.iter().collect();
+        if self.options.canonical {
+            canonical::sort_entries(&mut entries);
+        }
+        self.out.write_char('{')?;
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                self.out.write_char(',')?;
+            }
+            self.write_newline(depth + 1)?;
+            escape_string(&mut self.out, key, &self.options)?;
+            self.out.write_str(colon)?;
+            self.write_value(value, depth + 1)?;
+        }
+        if !object.is_empty() {
+            self.write_newline(depth)?;
+        }
+        self.out.write_char('}')?;
+        Ok(())
+    }
+
+    /// Starts a new line at `depth` when pretty printing; compact output has none.
+    fn write_newline(&mut self, depth: usize) -> fmt::Result {
+        match self.options.pretty {
+            Some(pretty) => pretty.write_newline(&mut self.out, depth),
+            None         => Ok(()),
+        }
+    }
+
+    fn write_number(&mut self, number: &Number) -> Result<(),EncodeError> {
+        if self.options.canonical {
+            // Canonical numbers go through `f64`, which a verbatim or decimal number may overflow
+            // even though its text is perfectly good JSON.
+            let n: f64 = number.as_f64();
+            if !n.is_finite() {
+                return Err(EncodeError::NonFiniteNumber(n));
+            }
+            self.out.write_str(&canonical::format_number(n))?;
+            return Ok(());
+        }
+        if number.is_finite() {
+            write!(self.out, "{}", number)?;
+            return Ok(());
+        }
+        let n: f64 = number.as_f64();
+        match self.options.non_finite {
+            NonFinite::Error  => return Err(EncodeError::NonFiniteNumber(n)),
+            NonFinite::Null   => self.out.write_str("null")?,
+            NonFinite::String => {
+                let name: &str = if n.is_nan() { "NaN" } else if n > 0.0 { "Infinity" } else { "-Infinity" };
+                escape_string(&mut self.out, name, &self.options)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Quotes `string` onto `out`, escaping what RFC 8259 requires: the quote, the backslash and the
+/// control characters U+0000 to U+001F. Runs of characters that need no escaping are written in
+/// one go.
+fn escape_string<W: Write>(out: &mut W, string: &str, options: &EncodeOptions) -> fmt::Result {
+    out.write_char('"')?;
+    let mut start: usize = 0;
+    for (i, ch) in string.char_indices() {
+        let escaped: bool = matches!(ch, '"' | '\\' | '\u{00}'..='\u{1f}') || (options.ascii_only && !ch.is_ascii());
+        if !escaped {
+            continue;
+        }
+        out.write_str(&string[start..i])?;
+        start = i + ch.len_utf8();
+        match ch {
+            '"'                 => out.write_str("\\\"")?,
+            '\\'                => out.write_str("\\\\")?,
+            '\u{08}'            => out.write_str("\\b")?,
+            '\u{0c}'            => out.write_str("\\f")?,
+            '\n'                => out.write_str("\\n")?,
+            '\r'                => out.write_str("\\r")?,
+            '\t'                => out.write_str("\\t")?,
+            _ => {
+                let mut units: [u16; 2] = [0; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            },
+        }
+    }
+    out.write_str(&string[start..])?;
+    out.write_char('"')
+}
+
+/// Collects a line of text up to `width` characters and refuses anything past that.
+struct Bounded {
+    line:  String,
+    chars: usize,
+    width: usize,
+}
+
+impl Bounded {
+    fn new(width: usize) -> Bounded {
+        Bounded { line: String::new(), chars: 0, width }
+    }
+
+    fn is_full(&self) -> bool {
+        self.chars > self.width
+    }
+}
+
+impl Write for Bounded {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.chars += s.chars().count();
+        if self.is_full() {
+            return Err(fmt::Error);
+        }
+        self.line.push_str(s);
+        Ok(())
+    }
+}
+
+/// Lets the writer target an `io::Write`. `fmt::Error` carries no detail, so the underlying I/O
+/// error is kept here for the caller to report.
+pub(crate) struct IoAdapter<W: io::Write> {
+    inner:            W,
+    pub(crate) error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoAdapter<W> {
+    pub(crate) fn new(inner: W) -> IoAdapter<W> {
+        IoAdapter { inner, error: None }
+    }
+}
+
+impl<W: io::Write> Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.inner.write_all(s.as_bytes()) {
+            Ok(())     => Ok(()),
+            Err(error) => {
+                self.error = Some(error);
+                Err(fmt::Error)
+            },
+        }
+    }
+}
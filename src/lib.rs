@@ -2,3 +2,12 @@ pub mod parsing;
 pub mod encoding;
 pub mod types;
 
+pub use parsing::{parse, parse_reader, parse_reader_with_options, parse_slice, parse_slice_with_options, parse_with_options,
+                  ErrorKind, FromJson, ParseError, ParseOptions, Parser, Position};
+pub use parsing::events::{Event, EventReader};
+pub use parsing::ndjson::{NdjsonReader};
+pub use parsing::push::{PushParser, PushStatus};
+pub use parsing::stream::{StreamReader};
+pub use encoding::{EncodeError, EncodeOptions, Indent, Newline, NonFinite, PrettyOptions, ToJson};
+pub use encoding::ndjson::{NdjsonWriter};
+pub use types::{Array, Decimal, Number, Object, Value};
@@ -0,0 +1,109 @@
+use parsing::{Parser, ErrorKind, ParseError, ParseOptions, FromJson, from_json_as};
+use parsing::value::{ValueParser};
+use types::{Array, Value};
+
+/// Parses a JSON array and nothing else: the first character must open it, and nothing may
+/// follow its closing bracket.
+pub struct ArrayParser {
+    parser:  ValueParser,
+    started: bool,
+}
+
+impl FromJson for Array {
+    fn from_json(json_string: &str) -> Result<Box<Array>,ParseError> {
+        from_json_as(json_string, &ParseOptions::default(), "array", |value| match value {
+            Value::Array(a) => Ok(a),
+            other => Err(other),
+        })
+    }
+}
+
+impl Default for ArrayParser {
+    fn default() -> ArrayParser {
+        ArrayParser::new()
+    }
+}
+
+impl ArrayParser {
+    pub fn new() -> ArrayParser {
+        ArrayParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> ArrayParser {
+        ArrayParser {
+            parser:  ValueParser::with_options(options),
+            started: false,
+        }
+    }
+}
+
+impl Parser for ArrayParser {
+    fn get_result(&self) -> Result<Value, ErrorKind> {
+        self.parser.get_result()
+    }
+    fn is_complete(&self) -> bool {
+        self.parser.is_complete()
+    }
+    fn has_result(&self) -> bool {
+        self.parser.has_result()
+    }
+    fn take_result(&mut self) -> Result<Value, ErrorKind> {
+        self.parser.take_result()
+    }
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
+        if (!self.started && ch != '[') || self.parser.is_complete() {
+            return Err(ErrorKind::UnexpectedToken(ch));
+        }
+        self.started = true;
+        self.parser.push_token(ch)
+    }
+}
+
+// HAPPY PATHS
+#[test]
+fn parse_empty_arrays() {
+    assert_eq!( *Array::from_json("[]").unwrap(), Array::new() );
+    assert_eq!( *Array::from_json("[ \n ]").unwrap(), Array::new() );
+}
+
+#[test]
+fn parse_arrays_of_values() {
+    assert_eq!( *Array::from_json("[1, \"a\" ,{}]").unwrap(),
+                vec![Value::Number(::types::Number::from(1)), Value::String("a".to_string()), Value::Object(::types::Object::new())] );
+}
+
+#[test]
+fn parse_nested_arrays() {
+    assert_eq!( *Array::from_json("[[],[[1]]]").unwrap(),
+                vec![Value::Array(Array::new()), Value::Array(vec![Value::Array(vec![Value::Number(::types::Number::from(1))])])] );
+    assert_eq!( ::parsing::parse("{\"a\":[1,2]}").unwrap(),
+                ::parsing::parse("{ \"a\" : [ 1 , 2 ] }").unwrap() );
+}
+
+#[test]
+fn finished_arrays_are_handed_over() {
+    let mut parser: ArrayParser = ArrayParser::new();
+    for ch in "[{\"a\":1},2]".chars() {
+        parser.push_token(ch).unwrap();
+    }
+    assert_eq!( parser.take_result().unwrap(), ::parsing::parse("[{\"a\":1},2]").unwrap() );
+    assert_eq!( parser.take_result(), Err(ErrorKind::UnexpectedEndOfInput) );
+    assert_eq!( parser.get_result(), Err(ErrorKind::UnexpectedEndOfInput) );
+}
+
+// SAD PATHS
+#[test]
+fn invalid_arrays_fail() {
+    assert!( Array::from_json("").is_err() );
+    assert!( Array::from_json("[").is_err() );
+    assert!( Array::from_json("[1").is_err() );
+    assert!( Array::from_json("[1]]").is_err() );
+    assert!( Array::from_json("{}").is_err() );
+}
+
+#[test]
+fn missing_values_and_duplicate_commas_fail() {
+    assert_eq!( Array::from_json("[,]").unwrap_err().kind(), &ErrorKind::UnexpectedToken(',') );
+    assert_eq!( Array::from_json("[1,]").unwrap_err().kind(), &ErrorKind::UnexpectedToken(']') );
+    assert_eq!( Array::from_json("[1,,2]").unwrap_err().kind(), &ErrorKind::UnexpectedToken(',') );
+    assert_eq!( Array::from_json("[1 2]").unwrap_err().kind(), &ErrorKind::UnexpectedToken('2') );
+}
@@ -0,0 +1,382 @@
+use std::io;
+use parsing::{ErrorKind, ParseError, ParseOptions, Parser, Position, is_whitespace, push_to_sub_parser};
+use parsing::literal::{LiteralParser};
+use parsing::number::{NumberParser};
+use parsing::source::{Source};
+use parsing::string::{StringParser};
+use types::{Number, Value};
+
+/// One step through a JSON document.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// The key of the object entry whose value comes next.
+    Key(String),
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+enum ParseState {
+    ExpectingValue,
+    ExpectingValueOrEndOfArray,
+    ExpectingKeyOrEndOfObject,
+    ExpectingKey,
+    ExpectingColon,
+    ExpectingCommaOrEnd,
+    DocumentFinished,
+    Failed,
+}
+
+/// A string, number or literal being read, with where it started.
+struct Scalar {
+    parser:   Box<dyn Parser>,
+    position: Position,
+    is_key:   bool,
+}
+
+/// Reads a JSON document as a sequence of `Event`s, each with the position of the token it came
+/// from, without building a `Value` for it. Since no `Value` is built, `ParseOptions::max_depth`
+/// can safely be raised far beyond what is wise for `parse`. Nothing is kept once its event has
+/// been handed out, so memory use depends only on the depth of the document and the longest
+/// single string or number in it.
+///
+/// The document is checked as strictly as `parse` checks it, and the first error ends the
+/// iteration.
+pub struct EventReader<'a> {
+    source:  Source<'a>,
+    options: ParseOptions,
+    state:   ParseState,
+    stack:   Vec<Container>,
+    /// How many keys or elements each open container has had so far.
+    entries: Vec<usize>,
+    scalar:  Option<Scalar>,
+}
+
+impl<'a> EventReader<'a> {
+    pub fn new(json_string: &'a str) -> EventReader<'a> {
+        EventReader::from_source(Source::new(json_string))
+    }
+    pub fn from_slice(bytes: &'a [u8]) -> EventReader<'a> {
+        EventReader::from_source(Source::from_slice(bytes))
+    }
+    pub fn from_reader<R: io::Read + 'a>(reader: R) -> EventReader<'a> {
+        EventReader::from_source(Source::from_reader(reader))
+    }
+    fn from_source(source: Source<'a>) -> EventReader<'a> {
+        EventReader {
+            source,
+            options: ParseOptions::default(),
+            state:   ParseState::ExpectingValue,
+            stack:   Vec::new(),
+            entries: Vec::new(),
+            scalar:  None,
+        }
+    }
+
+    /// Reads with `options` instead of the defaults. Only takes effect before the first event.
+    pub fn with_options(mut self, options: ParseOptions) -> EventReader<'a> {
+        self.options = options;
+        self.source.set_options(&options);
+        self
+    }
+
+    fn fail(&mut self, kind: ErrorKind, current: Option<char>) -> Option<Result<(Event,Position),ParseError>> {
+        self.state = ParseState::Failed;
+        Some(Err(self.source.tracker.error(kind, current)))
+    }
+
+    /// Moves on from a finished value to whatever may follow it.
+    fn value_finished(&mut self) {
+        self.state = if self.stack.is_empty() {
+            ParseState::DocumentFinished
+        } else {
+            ParseState::ExpectingCommaOrEnd
+        };
+    }
+
+    fn start_scalar(&mut self, ch: char, is_key: bool) -> Result<(),ErrorKind> {
+        let parser: Box<dyn Parser> = match ch {
+            '"'             => Box::new(StringParser::with_options(self.options)),
+            '-' | '0'..='9' => Box::new(NumberParser::with_options(self.options)),
+            't'             => Box::new(LiteralParser::new_true()),
+            'f'             => Box::new(LiteralParser::new_false()),
+            'n'             => Box::new(LiteralParser::new_null()),
+            _               => return Err(ErrorKind::UnexpectedToken(ch)),
+        };
+        self.count_entry(is_key)?;
+        let mut scalar: Scalar = Scalar { parser, position: self.source.tracker.position, is_key };
+        scalar.parser.push_token(ch)?;
+        self.scalar = Some(scalar);
+        Ok(())
+    }
+
+    /// The event for a finished string, number or literal.
+    fn scalar_finished(&mut self, scalar: Scalar, value: Value) -> (Event,Position) {
+        let event: Event = match value {
+            Value::String(s) if scalar.is_key => Event::Key(s),
+            Value::String(s)                  => Event::String(s),
+            Value::Number(n)                  => Event::Number(n),
+            Value::Boolean(b)                 => Event::Bool(b),
+            _                                 => Event::Null,
+        };
+        if scalar.is_key {
+            self.state = ParseState::ExpectingColon;
+        } else {
+            self.value_finished();
+        }
+        (event, scalar.position)
+    }
+
+    /// Counts a key, or an element of an array, against the limit for its container.
+    fn count_entry(&mut self, is_key: bool) -> Result<(),ErrorKind> {
+        let (limit, error): (usize, fn(usize) -> ErrorKind) = match self.stack.last() {
+            Some(Container::Object) if is_key => (self.options.max_object_keys, ErrorKind::TooManyObjectKeys),
+            Some(Container::Array)            => (self.options.max_array_elements, ErrorKind::TooManyArrayElements),
+            _                                 => return Ok(()),
+        };
+        let count: &mut usize = self.entries.last_mut().unwrap();
+        if *count >= limit {
+            return Err(error(limit));
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    fn open(&mut self, container: Container) -> Result<Option<(Event,Position)>,ErrorKind> {
+        if self.stack.len() >= self.options.max_depth {
+            return Err(ErrorKind::DepthLimitExceeded(self.options.max_depth));
+        }
+        self.count_entry(false)?;
+        let position: Position = self.source.tracker.position;
+        self.stack.push(container);
+        self.entries.push(0);
+        match container {
+            Container::Object => {
+                self.state = ParseState::ExpectingKeyOrEndOfObject;
+                Ok(Some((Event::StartObject, position)))
+            },
+            Container::Array => {
+                self.state = ParseState::ExpectingValueOrEndOfArray;
+                Ok(Some((Event::StartArray, position)))
+            },
+        }
+    }
+
+    fn close(&mut self) -> (Event,Position) {
+        let position: Position = self.source.tracker.position;
+        self.entries.pop();
+        let event: Event = match self.stack.pop() {
+            Some(Container::Object) => Event::EndObject,
+            _                       => Event::EndArray,
+        };
+        self.value_finished();
+        (event, position)
+    }
+
+    /// What to report when the input runs out.
+    fn end_of_input(&mut self) -> Option<Result<(Event,Position),ParseError>> {
+        if let Some(mut scalar) = self.scalar.take() {
+            return match scalar.parser.take_result() {
+                Ok(value) => Some(Ok(self.scalar_finished(scalar, value))),
+                Err(kind) => self.fail(kind, None),
+            };
+        }
+        let kind: ErrorKind = match (&self.state, self.stack.first()) {
+            (ParseState::DocumentFinished, _) | (ParseState::Failed, _) => return None,
+            (_, Some(Container::Object))                                => ErrorKind::UnterminatedToken('{'),
+            (_, Some(Container::Array))                                 => ErrorKind::UnterminatedToken('['),
+            (_, None)                                                   => ErrorKind::EmptyInput,
+        };
+        self.fail(kind, None)
+    }
+}
+
+impl<'a> Iterator for EventReader<'a> {
+    type Item = Result<(Event,Position),ParseError>;
+
+    fn next(&mut self) -> Option<Result<(Event,Position),ParseError>> {
+        loop {
+            if let ParseState::Failed = self.state {
+                return None;
+            }
+            let ch: char = match self.source.peek() {
+                None          => return self.end_of_input(),
+                Some(Err(e))  => return self.fail(e, None),
+                Some(Ok(ch))  => ch,
+            };
+            if let Err(error) = self.source.tracker.check_length(ch) {
+                self.state = ParseState::Failed;
+                return Some(Err(error));
+            }
+
+            if let Some(mut scalar) = self.scalar.take() {
+                match push_to_sub_parser(&mut scalar.parser, ch) {
+                    Ok(None) => {
+                        self.source.consume(ch);
+                        if scalar.parser.is_complete() {
+                            let value: Value = scalar.parser.take_result().unwrap();
+                            return Some(Ok(self.scalar_finished(scalar, value)));
+                        }
+                        self.scalar = Some(scalar);
+                    },
+                    Ok(Some(value)) => {
+                        return Some(Ok(self.scalar_finished(scalar, value)));
+                    },
+                    Err(kind) => {
+                        return self.fail(kind, Some(ch));
+                    },
+                }
+                continue;
+            }
+
+            if self.source.skip_bom(ch) {
+                continue;
+            }
+            if is_whitespace(ch) {
+                self.source.consume(ch);
+                continue;
+            }
+            let top: Option<Container> = self.stack.last().cloned();
+            let result: Result<Option<(Event,Position)>,ErrorKind> = match (&self.state, ch) {
+                (ParseState::ExpectingValueOrEndOfArray, ']')          => Ok(Some(self.close())),
+                (ParseState::ExpectingValue, '{')
+                | (ParseState::ExpectingValueOrEndOfArray, '{')        => self.open(Container::Object),
+                (ParseState::ExpectingValue, '[')
+                | (ParseState::ExpectingValueOrEndOfArray, '[')        => self.open(Container::Array),
+                (ParseState::ExpectingValue, _)
+                | (ParseState::ExpectingValueOrEndOfArray, _)          => self.start_scalar(ch, false).map(|_| None),
+                (ParseState::ExpectingKeyOrEndOfObject, '}')           => Ok(Some(self.close())),
+                (ParseState::ExpectingKeyOrEndOfObject, '"')
+                | (ParseState::ExpectingKey, '"')                      => self.start_scalar(ch, true).map(|_| None),
+                (ParseState::ExpectingColon, ':')                      => {
+                    self.state = ParseState::ExpectingValue;
+                    Ok(None)
+                },
+                (ParseState::ExpectingCommaOrEnd, ',')                 => {
+                    self.state = match top {
+                        Some(Container::Object) => ParseState::ExpectingKey,
+                        _                       => ParseState::ExpectingValue,
+                    };
+                    Ok(None)
+                },
+                (ParseState::ExpectingCommaOrEnd, '}') if top == Some(Container::Object) => Ok(Some(self.close())),
+                (ParseState::ExpectingCommaOrEnd, ']') if top == Some(Container::Array)  => Ok(Some(self.close())),
+                (ParseState::DocumentFinished, _)                      => Err(ErrorKind::TrailingCharacters(ch)),
+                _                                                      => Err(ErrorKind::UnexpectedToken(ch)),
+            };
+            match result {
+                Ok(event) => {
+                    self.source.consume(ch);
+                    if event.is_some() {
+                        return event.map(Ok);
+                    }
+                },
+                Err(kind) => {
+                    return self.fail(kind, Some(ch));
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn events(json_string: &str) -> Vec<Event> {
+    EventReader::new(json_string).map(|event| event.unwrap().0).collect()
+}
+
+#[test]
+fn documents_become_events() {
+    assert_eq!( events(" {\"a\": [1, \"two\", true, null], \"b\": {}, \"c\": -0.5} "), vec![
+        Event::StartObject,
+        Event::Key("a".to_string()),
+        Event::StartArray,
+        Event::Number(Number::from(1)),
+        Event::String("two".to_string()),
+        Event::Bool(true),
+        Event::Null,
+        Event::EndArray,
+        Event::Key("b".to_string()),
+        Event::StartObject,
+        Event::EndObject,
+        Event::Key("c".to_string()),
+        Event::Number(Number::from(-0.5)),
+        Event::EndObject,
+    ] );
+    assert_eq!( events("12"), vec![Event::Number(Number::from(12))] );
+    assert_eq!( events("[[]]"), vec![Event::StartArray, Event::StartArray, Event::EndArray, Event::EndArray] );
+}
+
+#[test]
+fn events_carry_the_position_of_their_token() {
+    let positions: Vec<(usize, usize)> = EventReader::new("{\n  \"k\": [10,\n  \"é\"]\n}")
+        .map(|event| { let p: Position = event.unwrap().1; (p.line, p.column) })
+        .collect();
+    assert_eq!( positions, vec![(1, 1), (2, 3), (2, 8), (2, 9), (3, 3), (3, 6), (4, 1)] );
+}
+
+#[test]
+fn readers_and_slices_produce_the_same_events() {
+    let json: &str = "{\"a\":[\"😀\",1e3]}";
+    let from_str: Vec<Event> = events(json);
+    let from_slice: Vec<Event> = EventReader::from_slice(json.as_bytes()).map(|event| event.unwrap().0).collect();
+    let from_reader: Vec<Event> = EventReader::from_reader(json.as_bytes()).map(|event| event.unwrap().0).collect();
+    assert_eq!( from_slice, from_str );
+    assert_eq!( from_reader, from_str );
+}
+
+#[test]
+fn deep_documents_do_not_recurse() {
+    let depth: usize = 100_000;
+    let json: String = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    let options: ParseOptions = ParseOptions { max_depth: depth, ..ParseOptions::default() };
+    assert_eq!( EventReader::new(&json).with_options(options).count(), 2 * depth );
+    let error: ParseError = EventReader::new(&json).last().unwrap().unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::DepthLimitExceeded(128) );
+    assert_eq!( error.position().offset, 128 );
+}
+
+#[test]
+fn errors_end_the_events() {
+    let mut reader: EventReader = EventReader::new("[1,]");
+    assert_eq!( reader.next().unwrap().unwrap().0, Event::StartArray );
+    assert_eq!( reader.next().unwrap().unwrap().0, Event::Number(Number::from(1)) );
+    let error: ParseError = reader.next().unwrap().unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnexpectedToken(']') );
+    assert_eq!( error.position().offset, 3 );
+    assert!( reader.next().is_none() );
+
+    let last = |json: &str| EventReader::new(json).last().unwrap().unwrap_err().kind().clone();
+    assert_eq!( last("{\"a\" 1}"), ErrorKind::UnexpectedToken('1') );
+    assert_eq!( last("{\"a\":1]"), ErrorKind::UnexpectedToken(']') );
+    assert_eq!( last("[{\"a\":"), ErrorKind::UnterminatedToken('[') );
+    assert_eq!( last("[1] 2"), ErrorKind::TrailingCharacters('2') );
+    assert_eq!( last("  "), ErrorKind::EmptyInput );
+    assert_eq!( last("[tru]"), ErrorKind::UnexpectedToken(']') );
+    assert_eq!( last("[\"a\nb\"]"), ErrorKind::UnescapedControlChar('\n') );
+}
+
+#[test]
+fn sizes_are_limited() {
+    let options: ParseOptions = ParseOptions { max_object_keys: 1, max_array_elements: 2, max_string_length: 4, ..ParseOptions::default() };
+    let last_error = |json_string: &str| EventReader::new(json_string).with_options(options).last().unwrap().map_err(|error| error.kind().clone());
+    assert!( last_error("[{\"key\":[1,{}]},\"four\"]").is_ok() );
+    assert_eq!( last_error("{\"a\":1,\"b\":2}"), Err(ErrorKind::TooManyObjectKeys(1)) );
+    assert_eq!( last_error("[[1,2],3,4]"), Err(ErrorKind::TooManyArrayElements(2)) );
+    assert!( last_error("[{},[]]").is_ok() );
+    assert_eq!( last_error("[[],{},[]]"), Err(ErrorKind::TooManyArrayElements(2)) );
+    assert_eq!( last_error("\"abcde\""), Err(ErrorKind::StringTooLong(4)) );
+
+    let options: ParseOptions = ParseOptions { max_input_bytes: 4, ..ParseOptions::default() };
+    assert_eq!( EventReader::new("[1,2]").with_options(options).last().unwrap().unwrap_err().kind(), &ErrorKind::InputTooLong(4) );
+}
@@ -0,0 +1,98 @@
+use parsing::{Parser, ErrorKind, ParseError, ParseOptions, FromJson, from_json_as};
+use types::{Value};
+
+/// Recognizes one of the keywords `true`, `false` or `null` a character at a time.
+pub struct LiteralParser {
+    keyword: &'static str,
+    value:   Value,
+    matched: usize,
+}
+
+impl FromJson for bool {
+    fn from_json(json_string: &str) -> Result<Box<bool>,ParseError> {
+        from_json_as(json_string, &ParseOptions::default(), "boolean", |value| match value {
+            Value::Boolean(b) => Ok(b),
+            other => Err(other),
+        })
+    }
+}
+
+impl LiteralParser {
+    fn new(keyword: &'static str, value: Value) -> LiteralParser {
+        LiteralParser {
+            keyword,
+            value,
+            matched: 0,
+        }
+    }
+    pub fn new_true() -> LiteralParser {
+        LiteralParser::new("true", Value::Boolean(true))
+    }
+    pub fn new_false() -> LiteralParser {
+        LiteralParser::new("false", Value::Boolean(false))
+    }
+    pub fn new_null() -> LiteralParser {
+        LiteralParser::new("null", Value::None)
+    }
+}
+
+impl Parser for LiteralParser {
+    fn get_result(&self) -> Result<Value, ErrorKind> {
+        if self.matched == 0 {
+            Err(ErrorKind::EmptyInput)
+        } else if self.matched < self.keyword.len() {
+            Err(ErrorKind::UnexpectedEndOfInput)
+        } else {
+            Ok(self.value.clone())
+        }
+    }
+    fn is_complete(&self) -> bool {
+        self.matched == self.keyword.len()
+    }
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
+        match self.keyword.as_bytes().get(self.matched) {
+            Some(&expected) if expected as char == ch => {
+                self.matched += 1;
+                Ok(())
+            },
+            _ => {
+                Err(ErrorKind::UnexpectedToken(ch))
+            },
+        }
+    }
+}
+
+// HAPPY PATHS
+#[test]
+fn parse_booleans() {
+    assert!( *bool::from_json("true").unwrap() );
+    assert!( !*bool::from_json("false").unwrap() );
+}
+
+#[test]
+fn parse_literals_as_values() {
+    assert_eq!( ::parsing::parse("true").unwrap(), Value::Boolean(true) );
+    assert_eq!( ::parsing::parse(" false ").unwrap(), Value::Boolean(false) );
+    assert_eq!( ::parsing::parse("null").unwrap(), Value::None );
+    assert_eq!( ::parsing::parse("[true,false,null]").unwrap(),
+                Value::Array(vec![Value::Boolean(true), Value::Boolean(false), Value::None]) );
+    assert_eq!( ::parsing::parse("{\"a\":null}").unwrap(), ::parsing::parse("{ \"a\" : null }").unwrap() );
+}
+
+// SAD PATHS
+#[test]
+fn partial_literals_fail() {
+    assert_eq!( ::parsing::parse("nul").unwrap_err().kind(), &ErrorKind::UnexpectedEndOfInput );
+    assert_eq!( ::parsing::parse("t").unwrap_err().kind(), &ErrorKind::UnexpectedEndOfInput );
+    assert_eq!( bool::from_json("fals").unwrap_err().kind(), &ErrorKind::UnexpectedEndOfInput );
+}
+
+#[test]
+fn misspelled_literals_fail() {
+    assert_eq!( ::parsing::parse("nulll").unwrap_err().kind(), &ErrorKind::TrailingCharacters('l') );
+    assert_eq!( ::parsing::parse("tru e").unwrap_err().kind(), &ErrorKind::UnexpectedToken(' ') );
+    assert_eq!( ::parsing::parse("True").unwrap_err().kind(), &ErrorKind::UnexpectedToken('T') );
+    assert_eq!( bool::from_json(" null").unwrap_err().kind(), &ErrorKind::TypeMismatch { expected: "boolean", found: "null" } );
+    assert_eq!( bool::from_json(" null").unwrap_err().position().column, 2 );
+    assert!( bool::from_json("").is_err() );
+}
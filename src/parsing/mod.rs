@@ -1,23 +1,578 @@
 pub mod string;
 pub mod number;
 pub mod object;
+pub mod array;
 pub mod nil;
+pub mod literal;
+pub mod value;
+pub mod utf8;
+pub mod push;
+pub mod events;
+pub mod ndjson;
+pub mod stream;
+mod source;
+use std::collections::{VecDeque};
+use std::error::{Error};
+use std::fmt;
+use std::io;
+use parsing::nil::{NilParser};
+use parsing::utf8::{ReadBytes, Utf8Chars};
+use parsing::value::{ValueParser};
 use types::{Value};
 
-#[derive(Debug)]
-enum ParseError {
+/// Knobs for how strictly input is read. The defaults follow RFC 8259.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseOptions {
+    /// Accept raw U+0000 to U+001F characters inside strings instead of requiring them to be
+    /// escaped.
+    pub allow_control_chars: bool,
+    /// Keep numbers an `f64` cannot hold exactly, such as integers too large for 64 bits or
+    /// decimals with more digits than it carries, as their original text instead of rounding them
+    /// to the nearest `f64`.
+    pub arbitrary_precision: bool,
+    /// Read every number as a `Decimal` holding its exact text, never converting through `f64`.
+    pub decimal_numbers: bool,
+    /// Skip a byte order mark (U+FEFF) at the very start of the input.
+    pub skip_bom: bool,
+    /// How deeply objects and arrays may nest. Defaults to 128. Parsing keeps its own stack, but
+    /// dropping, comparing or encoding a `Value` recurses once per level, so a limit beyond a few
+    /// thousand lets hostile input overflow the stack later on. `EventReader` builds no `Value`
+    /// and is safe with any limit.
+    pub max_depth: usize,
+    /// The most bytes of input to read. `NdjsonReader` applies it to each line and `StreamReader`
+    /// to each value, counting from the end of the one before, so a long stream of small values
+    /// is fine. Like the limits below, this defaults to no limit at all.
+    pub max_input_bytes: usize,
+    /// The most bytes a string or key may hold once its escapes are decoded.
+    pub max_string_length: usize,
+    /// The most characters a number literal may have.
+    pub max_number_length: usize,
+    /// The most entries an object may have.
+    pub max_object_keys: usize,
+    /// The most elements an array may have.
+    pub max_array_elements: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            allow_control_chars: false,
+            arbitrary_precision: false,
+            decimal_numbers:     false,
+            skip_bom:            false,
+            max_depth:           128,
+            max_input_bytes:     usize::MAX,
+            max_string_length:   usize::MAX,
+            max_number_length:   usize::MAX,
+            max_object_keys:     usize::MAX,
+            max_array_elements:  usize::MAX,
+        }
+    }
+}
+
+/// What went wrong while reading JSON, without saying where.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// A character that cannot appear at this point.
     UnexpectedToken(char),
+    /// A complete value was followed by something other than whitespace.
+    TrailingCharacters(char),
+    /// A string, array or object opened with this character was never closed.
     UnterminatedToken(char),
+    /// The input stopped in the middle of a token.
     UnexpectedEndOfInput,
-    EmptyStringGiven,
+    /// The input held nothing but whitespace.
+    EmptyInput,
+    /// A malformed number literal, as far as it was read.
+    InvalidNumber(String),
+    /// A valid number read as a `Decimal` whose exponent is too large to hold.
+    ExponentOutOfRange(String),
+    /// A backslash escape in a string that JSON does not define.
+    InvalidEscape(char),
+    /// A `\u` escape for a code point that is not a Unicode scalar value.
     InvalidUnicodeChar(u32),
+    /// A `\u` escape for one half of a surrogate pair without the other half.
+    UnpairedSurrogate(u32),
+    /// A control character written into a string as is, which must be escaped instead.
+    UnescapedControlChar(char),
+    /// A byte that cannot appear at this point of a UTF-8 sequence.
+    InvalidUtf8(u8),
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// Objects and arrays nested deeper than this limit.
+    DepthLimitExceeded(usize),
+    /// The input went on for more bytes than this limit.
+    InputTooLong(usize),
+    /// A string or key that decodes to more bytes than this limit.
+    StringTooLong(usize),
+    /// A number literal with more characters than this limit.
+    NumberTooLong(usize),
+    /// An object with more entries than this limit.
+    TooManyObjectKeys(usize),
+    /// An array with more elements than this limit.
+    TooManyArrayElements(usize),
+    /// The input held valid JSON, but not the kind of value that was asked for.
+    TypeMismatch { expected: &'static str, found: &'static str },
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::UnexpectedToken(ch)      => write!(f, "unexpected character {:?}", ch),
+            ErrorKind::TrailingCharacters(ch)   => write!(f, "trailing character {:?} after value", ch),
+            ErrorKind::UnterminatedToken(ch)    => write!(f, "unterminated token opened with {:?}", ch),
+            ErrorKind::UnexpectedEndOfInput     => write!(f, "unexpected end of input"),
+            ErrorKind::EmptyInput               => write!(f, "no JSON value found in input"),
+            ErrorKind::InvalidNumber(ref n)     => write!(f, "invalid number {:?}", n),
+            ErrorKind::ExponentOutOfRange(ref n) => write!(f, "exponent of number {:?} out of range", n),
+            ErrorKind::InvalidEscape(ch)        => write!(f, "invalid escape character {:?}", ch),
+            ErrorKind::InvalidUnicodeChar(n)    => write!(f, "invalid unicode code point U+{:04X}", n),
+            ErrorKind::UnpairedSurrogate(n)     => write!(f, "unpaired UTF-16 surrogate \\u{:04x}", n),
+            ErrorKind::UnescapedControlChar(ch) => write!(f, "unescaped control character U+{:04X} in string", ch as u32),
+            ErrorKind::InvalidUtf8(byte)        => write!(f, "invalid UTF-8 byte 0x{:02x}", byte),
+            ErrorKind::Io(kind)                 => write!(f, "I/O error: {}", kind),
+            ErrorKind::DepthLimitExceeded(n)    => write!(f, "objects and arrays nested more than {} deep", n),
+            ErrorKind::InputTooLong(n)          => write!(f, "input longer than {} bytes", n),
+            ErrorKind::StringTooLong(n)         => write!(f, "string longer than {} bytes", n),
+            ErrorKind::NumberTooLong(n)         => write!(f, "number longer than {} characters", n),
+            ErrorKind::TooManyObjectKeys(n)     => write!(f, "object with more than {} keys", n),
+            ErrorKind::TooManyArrayElements(n)  => write!(f, "array with more than {} elements", n),
+            ErrorKind::TypeMismatch { expected, found } => write!(f, "expected {} but found {}", expected, found),
+        }
+    }
+}
+
+/// A place in the input. Lines and columns count from 1, columns and the byte offset count
+/// characters and bytes respectively.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line:   usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl Position {
+    fn start() -> Position {
+        Position { line: 1, column: 1, offset: 0 }
+    }
+
+    fn advance(&mut self, ch: char) {
+        self.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.line  += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+/// Everything that can go wrong while reading JSON, along with where it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    kind:     ErrorKind,
+    position: Position,
+    snippet:  String,
+}
+
+impl ParseError {
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+    pub fn position(&self) -> Position {
+        self.position
+    }
+    /// A little of the input around where the error happened.
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line {}, column {} (byte {}) near {:?}",
+               self.kind, self.position.line, self.position.column, self.position.offset, self.snippet)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Types that can be read from the JSON text of exactly one value of their kind.
+pub trait FromJson {
+    fn from_json(json_string: &str) -> Result<Box<Self>,ParseError>;
+}
+
+/// A character-at-a-time state machine for one JSON value.
+///
+/// `push_token` rejects a character that cannot continue the value with
+/// `ErrorKind::UnexpectedToken`; if `has_result` holds at that point the value simply ended
+/// before that character. `get_result` can be called at any time and fails while the value is
+/// still incomplete. Parsers only say what went wrong; where it went wrong is worked out by
+/// whatever feeds them characters.
+pub trait Parser {
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind>;
+    fn get_result(&self) -> Result<Value, ErrorKind>;
+
+    /// Whether the value is finished, so that no further character could extend it. A number
+    /// never knows this until a character that cannot belong to it arrives.
+    fn is_complete(&self) -> bool {
+        false
+    }
+
+    /// Whether `get_result` would succeed. Parsers that build up a string, array or object
+    /// answer without copying it.
+    fn has_result(&self) -> bool {
+        self.get_result().is_ok()
+    }
+
+    /// Like `get_result`, but hands the value over rather than copying it, leaving the parser
+    /// spent. Parsers that build up a string, array or object move it out here, so finishing a
+    /// nested value costs nothing however deep it sits.
+    fn take_result(&mut self) -> Result<Value, ErrorKind> {
+        self.get_result()
+    }
+}
+
+/// How many characters either side of an error end up in its snippet.
+const SNIPPET_CONTEXT: usize = 16;
+
+/// Keeps track of the position of each character fed to a parser so its errors can be turned
+/// into `ParseError`s.
+struct Tracker {
+    position:        Position,
+    recent:          VecDeque<char>,
+    skip_bom:        bool,
+    max_input_bytes: usize,
+    /// The offset that bytes are counted from against `max_input_bytes`.
+    counted_from:    usize,
+}
+
+impl Tracker {
+    fn new(options: &ParseOptions) -> Tracker {
+        Tracker {
+            position:        Position::start(),
+            recent:          VecDeque::with_capacity(SNIPPET_CONTEXT),
+            skip_bom:        options.skip_bom,
+            max_input_bytes: options.max_input_bytes,
+            counted_from:    0,
+        }
+    }
+
+    /// Feeds `ch` to the parser for a whole document, where a rejected character after a complete
+    /// value can only be trailing garbage.
+    fn push_token(&mut self, parser: &mut dyn Parser, ch: char) -> Result<(),ParseError> {
+        self.check_length(ch)?;
+        if self.skip_bom(ch) {
+            return Ok(());
+        }
+        match parser.push_token(ch) {
+            Ok(_) => {
+                self.advance(ch);
+                Ok(())
+            },
+            Err(ErrorKind::UnexpectedToken(_)) if parser.has_result() => {
+                Err(self.error(ErrorKind::TrailingCharacters(ch), Some(ch)))
+            },
+            Err(kind) => {
+                Err(self.error(kind, Some(ch)))
+            },
+        }
+    }
+
+    /// Fails if reading `ch` would take the input past `max_input_bytes`.
+    fn check_length(&self, ch: char) -> Result<(),ParseError> {
+        let counted: usize = self.position.offset - self.counted_from;
+        if self.max_input_bytes.saturating_sub(counted) < ch.len_utf8() {
+            Err(self.error(ErrorKind::InputTooLong(self.max_input_bytes), Some(ch)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Counts bytes against `max_input_bytes` afresh from here on.
+    fn restart_count(&mut self) {
+        self.counted_from = self.position.offset;
+    }
+
+    /// Steps over `ch` if it is a byte order mark opening the input and those are being skipped.
+    /// It counts towards the byte offset but is not text.
+    fn skip_bom(&mut self, ch: char) -> bool {
+        if ch == '\u{feff}' && self.skip_bom && self.position.offset == 0 {
+            self.position.offset += ch.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn get_result(&self, parser: &mut dyn Parser) -> Result<Value,ParseError> {
+        parser.take_result().map_err(|kind| self.error(kind, None))
+    }
+
+    fn advance(&mut self, ch: char) {
+        self.position.advance(ch);
+        if self.recent.len() == SNIPPET_CONTEXT {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(ch);
+    }
+
+    /// Builds an error at the current position. The snippet is the input read so far up to and
+    /// including `current`, the character being rejected.
+    fn error(&self, kind: ErrorKind, current: Option<char>) -> ParseError {
+        let mut snippet: String = self.recent.iter().collect();
+        snippet.extend(current);
+        ParseError {
+            kind,
+            position: self.position,
+            snippet,
+        }
+    }
+}
+
+/// Runs `parser` over every character of the input, adding the text following an error to its
+/// snippet. Characters that could not be read end the input with an error of their own.
+fn parse_chars<P, I>(mut parser: P, chars: I, options: &ParseOptions) -> Result<Value,ParseError>
+    where P: Parser, I: Iterator<Item = Result<char,ErrorKind>> {
+    let mut tracker: Tracker = Tracker::new(options);
+    let mut chars: I = chars;
+    while let Some(ch) = chars.next() {
+        let ch: char = ch.map_err(|kind| tracker.error(kind, None))?;
+        if let Err(mut error) = tracker.push_token(&mut parser, ch) {
+            error.snippet.extend(chars.take(SNIPPET_CONTEXT).map_while(Result::ok));
+            return Err(error);
+        }
+    }
+    tracker.get_result(&mut parser)
+}
+
+/// Parses `json_string` as a single value and pulls the `expected` kind of value out of it with
+/// `unwrap`, which hands back anything else unchanged.
+fn from_json_as<T>(json_string: &str, options: &ParseOptions, expected: &'static str, unwrap: fn(Value) -> Result<T,Value>)
+    -> Result<Box<T>,ParseError> {
+    match unwrap(parse_with_options(json_string, options)?) {
+        Ok(t) => {
+            Ok(Box::new(t))
+        },
+        Err(value) => {
+            let start: usize = json_string.len() - json_string.trim_start_matches(is_whitespace).len();
+            let mut tracker: Tracker = Tracker::new(options);
+            for ch in json_string[..start].chars() {
+                tracker.advance(ch);
+            }
+            let mut error: ParseError = tracker.error(ErrorKind::TypeMismatch { expected, found: value.type_name() }, None);
+            error.snippet.extend(json_string[start..].chars().take(SNIPPET_CONTEXT));
+            Err(error)
+        },
+    }
+}
+
+/// Parses a complete JSON document. Insignificant whitespace around the value is skipped and
+/// anything else after it is rejected.
+pub fn parse(json_string: &str) -> Result<Value,ParseError> {
+    parse_with_options(json_string, &ParseOptions::default())
+}
+
+pub fn parse_with_options(json_string: &str, options: &ParseOptions) -> Result<Value,ParseError> {
+    parse_chars(ValueParser::with_options(*options), json_string.chars().map(Ok), options)
+}
+
+/// Parses a complete JSON document from UTF-8 bytes, checking the encoding as it goes.
+pub fn parse_slice(bytes: &[u8]) -> Result<Value,ParseError> {
+    parse_slice_with_options(bytes, &ParseOptions::default())
+}
+
+pub fn parse_slice_with_options(bytes: &[u8], options: &ParseOptions) -> Result<Value,ParseError> {
+    let chars = Utf8Chars::new(bytes.iter().map(|&byte| Ok(byte)));
+    parse_chars(ValueParser::with_options(*options), chars, options)
+}
+
+/// Parses a complete JSON document from UTF-8 bytes read until `reader` runs dry. Reading is
+/// buffered internally, so there is no need to wrap `reader` in a `BufReader`.
+pub fn parse_reader<R: io::Read>(reader: R) -> Result<Value,ParseError> {
+    parse_reader_with_options(reader, &ParseOptions::default())
+}
+
+pub fn parse_reader_with_options<R: io::Read>(reader: R, options: &ParseOptions) -> Result<Value,ParseError> {
+    let chars = Utf8Chars::new(ReadBytes::new(reader));
+    parse_chars(ValueParser::with_options(*options), chars, options)
+}
+
+/// The four characters JSON treats as insignificant whitespace between tokens.
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Feeds `ch` to a nested parser. Values such as numbers only know they have ended once they see
+/// a character that cannot belong to them, so when the sub-parser rejects `ch` while already
+/// holding a complete value, that value is handed back and `ch` is left for the caller to
+/// process. The spent sub-parser is dropped straight away.
+fn push_to_sub_parser(sub_parser: &mut Box<dyn Parser>, ch: char) -> Result<Option<Value>,ErrorKind> {
+    match sub_parser.push_token(ch) {
+        Ok(_) => Ok(None),
+        Err(error @ ErrorKind::UnexpectedToken(_)) => {
+            match sub_parser.take_result() {
+                Ok(value) => {
+                    *sub_parser = Box::new(NilParser::new());
+                    Ok(Some(value))
+                },
+                Err(_) => Err(error),
+            }
+        },
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+use parsing::string::{StringParser};
+
+#[test]
+fn finished_sub_parsers_hand_over_their_value() {
+    let mut sub_parser: Box<dyn Parser> = Box::new(StringParser::new());
+    for ch in "\"abc\"".chars() {
+        assert_eq!( push_to_sub_parser(&mut sub_parser, ch), Ok(None) );
+    }
+    assert_eq!( push_to_sub_parser(&mut sub_parser, ','), Ok(Some(Value::String("abc".to_string()))) );
+    assert_eq!( sub_parser.get_result(), Err(ErrorKind::UnexpectedEndOfInput) );
+}
+
+#[test]
+fn parse_errors_display_a_message() {
+    assert_eq!( ErrorKind::UnexpectedToken('x').to_string(), "unexpected character 'x'" );
+    assert_eq!( ErrorKind::InvalidUnicodeChar(0xd800).to_string(), "invalid unicode code point U+D800" );
+    assert_eq!( parse("[1,").unwrap_err().to_string(),
+                "unterminated token opened with '[' at line 1, column 4 (byte 3) near \"[1,\"" );
+}
+
+#[test]
+fn parse_errors_report_their_position() {
+    let error: ParseError = parse("{\n  \"a\": 1,\n  \"é\": tru\n}").unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnexpectedToken('\n') );
+    assert_eq!( error.position(), Position { line: 3, column: 11, offset: 23 } );
+    assert_eq!( error.snippet(), "\": 1,\n  \"é\": tru\n}" );
+}
+
+#[test]
+fn parse_error_snippets_are_trimmed_to_the_surrounding_input() {
+    let error: ParseError = parse("[0,1,2,3,4,5,6,7,8,9,x,10,11,12,13,14,15,16]").unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnexpectedToken('x') );
+    assert_eq!( error.position().offset, 21 );
+    assert_eq!( error.snippet(), "2,3,4,5,6,7,8,9,x,10,11,12,13,14," );
 }
 
-trait FromJson {
-    fn from_json(&str) -> Result<Box<Self>,ParseError>;
+#[test]
+fn end_of_input_errors_point_past_the_input() {
+    let error: ParseError = parse("\"abc").unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnterminatedToken('"') );
+    assert_eq!( error.position(), Position { line: 1, column: 5, offset: 4 } );
 }
 
-trait Parser {
-    fn push_token(&mut self, ch: char) -> Result<(),ParseError>;
-    fn get_result(&self) -> Result<Value, ParseError>;
+#[test]
+fn bytes_and_readers_parse_like_strings() {
+    let json: &str = "{\"name\":\"Zoë\",\"tags\":[1,\"😀\"]}";
+    assert_eq!( parse_slice(json.as_bytes()).unwrap(), parse(json).unwrap() );
+    assert_eq!( parse_reader(json.as_bytes()).unwrap(), parse(json).unwrap() );
+    let long: String = format!("[{}0]", "0,".repeat(10_000));
+    assert_eq!( parse_reader(long.as_bytes()).unwrap(), parse(&long).unwrap() );
 }
+
+#[test]
+fn invalid_utf8_is_reported_where_it_starts() {
+    let error: ParseError = parse_slice(b"[\"ab\xff\"]").unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::InvalidUtf8(0xff) );
+    assert_eq!( error.position().offset, 4 );
+    assert_eq!( parse_reader(&b"\"\xe2\x82"[..]).unwrap_err().kind(), &ErrorKind::UnexpectedEndOfInput );
+}
+
+#[test]
+fn byte_order_marks_are_skipped_on_request() {
+    let options: ParseOptions = ParseOptions { skip_bom: true, ..ParseOptions::default() };
+    assert_eq!( parse_slice_with_options(b"\xef\xbb\xbf[1]", &options).unwrap(), parse("[1]").unwrap() );
+    assert_eq!( parse_with_options("\u{feff}true", &options).unwrap(), Value::Boolean(true) );
+    assert_eq!( parse_slice(b"\xef\xbb\xbf[1]").unwrap_err().kind(), &ErrorKind::UnexpectedToken('\u{feff}') );
+    assert_eq!( parse_slice_with_options(b"\xef\xbb\xbf[x]", &options).unwrap_err().position().offset, 4 );
+}
+
+#[test]
+fn read_errors_are_reported() {
+    struct Broken;
+    impl io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"))
+        }
+    }
+    assert_eq!( parse_reader(Broken).unwrap_err().kind(), &ErrorKind::Io(io::ErrorKind::ConnectionReset) );
+}
+
+#[test]
+fn nesting_is_limited() {
+    let nested = |depth: usize| format!("{}0{}", "[{\"a\":".repeat(depth / 2), "}]".repeat(depth / 2));
+    assert!( parse(&nested(128)).is_ok() );
+
+    let error: ParseError = parse(&nested(130)).unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::DepthLimitExceeded(128) );
+    assert_eq!( error.position().offset, 6 * 64 );
+
+    let options: ParseOptions = ParseOptions { max_depth: 2, ..ParseOptions::default() };
+    assert!( parse_with_options("[[1],{\"a\":2}]", &options).is_ok() );
+    assert_eq!( parse_with_options("[[[]]]", &options).unwrap_err().kind(), &ErrorKind::DepthLimitExceeded(2) );
+    assert_eq!( parse_with_options("[{\"a\":{}}]", &options).unwrap_err().kind(), &ErrorKind::DepthLimitExceeded(2) );
+
+    let hostile: String = "[".repeat(1_000_000);
+    assert_eq!( parse(&hostile).unwrap_err().kind(), &ErrorKind::DepthLimitExceeded(128) );
+}
+
+#[test]
+fn deep_nesting_does_not_recurse() {
+    let depth: usize = 4_000;
+    let options: ParseOptions = ParseOptions { max_depth: depth, ..ParseOptions::default() };
+    let nested = |depth: usize| format!("{}0{}", "[{\"a\":".repeat(depth / 2), "}]".repeat(depth / 2));
+    assert!( parse_with_options(&nested(depth), &options).is_ok() );
+    assert_eq!( parse_with_options(&nested(depth + 2), &options).unwrap_err().kind(), &ErrorKind::DepthLimitExceeded(depth) );
+}
+
+#[test]
+fn long_values_parse_inside_deep_nesting() {
+    let text: String = "x".repeat(1 << 18);
+    let mut value: Value = parse(&format!("{}\"{}\"{}", "[{\"a\":".repeat(60), text, "}]".repeat(60))).unwrap();
+    for _ in 0..60 {
+        value = match value {
+            Value::Array(mut array) => match array.pop() {
+                Some(Value::Object(mut object)) => object.remove("a").unwrap(),
+                other                           => panic!("expected an object, found {:?}", other),
+            },
+            other => panic!("expected an array, found {:?}", other),
+        };
+    }
+    assert_eq!( value, Value::String(text) );
+}
+
+#[test]
+fn sizes_are_limited() {
+    let limit = |options: ParseOptions, json_string: &str| parse_with_options(json_string, &options).map_err(|error| error.kind().clone());
+    let defaults: ParseOptions = ParseOptions::default();
+
+    let options: ParseOptions = ParseOptions { max_input_bytes: 8, ..defaults };
+    assert!( limit(options, "[\"abcd\"]").is_ok() );
+    assert_eq!( limit(options, "[\"abcde\"]"), Err(ErrorKind::InputTooLong(8)) );
+    assert_eq!( limit(options, "\"abcdef\u{e9}\""), Err(ErrorKind::InputTooLong(8)) );
+    assert_eq!( parse_slice_with_options(b"[1, 2, 3, 4]", &options).unwrap_err().position().offset, 8 );
+
+    let options: ParseOptions = ParseOptions { max_string_length: 3, ..defaults };
+    assert!( limit(options, "[\"abc\", \"\\u00e9\"]").is_ok() );
+    assert_eq!( limit(options, "\"abcd\""), Err(ErrorKind::StringTooLong(3)) );
+    assert_eq!( limit(options, "\"\u{e9}\u{e9}\""), Err(ErrorKind::StringTooLong(3)) );
+    assert_eq!( limit(options, "{\"long\":1}"), Err(ErrorKind::StringTooLong(3)) );
+
+    let options: ParseOptions = ParseOptions { max_number_length: 4, ..defaults };
+    assert!( limit(options, "[-1.5, 1e10]").is_ok() );
+    assert_eq!( limit(options, "12345"), Err(ErrorKind::NumberTooLong(4)) );
+    assert_eq!( limit(options, "[-1.25]"), Err(ErrorKind::NumberTooLong(4)) );
+
+    let options: ParseOptions = ParseOptions { max_object_keys: 2, max_array_elements: 2, ..defaults };
+    assert!( limit(options, "{\"a\":[1,2],\"b\":{}}").is_ok() );
+    assert_eq!( limit(options, "{\"a\":1,\"b\":2,\"c\":3}"), Err(ErrorKind::TooManyObjectKeys(2)) );
+    assert_eq!( limit(options, "[1,2,3]"), Err(ErrorKind::TooManyArrayElements(2)) );
+    assert_eq!( limit(options, "[[],[1,2,[]]]"), Err(ErrorKind::TooManyArrayElements(2)) );
+}
+
@@ -0,0 +1,216 @@
+use std::io::{self, BufRead, Read};
+use parsing::{ErrorKind, ParseError, ParseOptions, Position, Tracker, parse_slice_with_options};
+use types::{Value};
+
+/// Reads newline-delimited JSON (NDJSON, also known as JSON Lines): one value per line. Lines
+/// holding nothing but whitespace are skipped.
+///
+/// Errors carry the line number and byte offset within the whole stream. By default a bad line
+/// is reported and reading carries on with the next one; `stop_on_error` ends the iteration at
+/// the first error instead. Read errors always end it.
+pub struct NdjsonReader<R: io::BufRead> {
+    reader:        R,
+    options:       ParseOptions,
+    stop_on_error: bool,
+    line:          Vec<u8>,
+    /// Where the next line starts.
+    position:      Position,
+    finished:      bool,
+}
+
+impl<R: io::BufRead> NdjsonReader<R> {
+    pub fn new(reader: R) -> NdjsonReader<R> {
+        NdjsonReader::with_options(reader, ParseOptions::default())
+    }
+    pub fn with_options(reader: R, options: ParseOptions) -> NdjsonReader<R> {
+        NdjsonReader {
+            reader,
+            options,
+            stop_on_error: false,
+            line:          Vec::new(),
+            position:      Position::start(),
+            finished:      false,
+        }
+    }
+
+    pub fn stop_on_error(mut self, stop: bool) -> NdjsonReader<R> {
+        self.stop_on_error = stop;
+        self
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// The line just read, without the `\n` or `\r\n` that ends it.
+    fn text(&self) -> &[u8] {
+        self.line.strip_suffix(b"\r\n").or_else(|| self.line.strip_suffix(b"\n")).unwrap_or(&self.line)
+    }
+
+    /// Parses the line just read, which starts at `start`.
+    fn parse_line(&self, start: Position) -> Result<Value,ParseError> {
+        let options: ParseOptions = ParseOptions { skip_bom: self.options.skip_bom && start.offset == 0, ..self.options };
+        parse_slice_with_options(self.text(), &options).map_err(|error| in_stream(error, start))
+    }
+
+    /// The error for the line just read, which starts at `start` and runs past `max_input_bytes`.
+    fn too_long(&self, start: Position) -> ParseError {
+        let max: usize = self.options.max_input_bytes;
+        let mut tracker: Tracker = Tracker::new(&self.options);
+        for ch in String::from_utf8_lossy(&self.line[..max]).chars() {
+            tracker.advance(ch);
+        }
+        let mut error: ParseError = tracker.error(ErrorKind::InputTooLong(max), None);
+        error.position.offset = max;
+        in_stream(error, start)
+    }
+
+    /// Reads past the rest of the current line without keeping it, returning how many bytes that
+    /// took.
+    fn skip_line(&mut self) -> io::Result<usize> {
+        let mut skipped: usize = 0;
+        loop {
+            let (used, found): (usize, bool) = match self.reader.fill_buf() {
+                Ok([]) => {
+                    return Ok(skipped);
+                },
+                Ok(bytes) => match bytes.iter().position(|&byte| byte == b'\n') {
+                    Some(i) => (i + 1, true),
+                    None    => (bytes.len(), false),
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                },
+                Err(e) => {
+                    return Err(e);
+                },
+            };
+            self.reader.consume(used);
+            skipped += used;
+            if found {
+                return Ok(skipped);
+            }
+        }
+    }
+}
+
+/// Moves an error found in a line on its own to where that line, starting at `start`, sits in the
+/// whole stream.
+fn in_stream(mut error: ParseError, start: Position) -> ParseError {
+    error.position.line   += start.line - 1;
+    error.position.offset += start.offset;
+    error
+}
+
+fn read_error(e: io::Error, position: Position) -> ParseError {
+    ParseError { kind: ErrorKind::Io(e.kind()), position, snippet: String::new() }
+}
+
+impl<R: io::BufRead> Iterator for NdjsonReader<R> {
+    type Item = Result<Value,ParseError>;
+
+    fn next(&mut self) -> Option<Result<Value,ParseError>> {
+        let max: usize = self.options.max_input_bytes;
+        while !self.finished {
+            let start: Position = self.position;
+            self.line.clear();
+            // Room for the limit and a `\r\n` is enough to tell whether a line is too long.
+            let limit: u64 = (max as u64).saturating_add(2);
+            match (&mut self.reader).take(limit).read_until(b'\n', &mut self.line) {
+                Ok(0) => {
+                    self.finished = true;
+                },
+                Ok(n) => {
+                    self.position.line   += 1;
+                    self.position.offset += n;
+                    if self.text().len() > max {
+                        let error: ParseError = self.too_long(start);
+                        let skipped: io::Result<usize> = if self.line.ends_with(b"\n") { Ok(0) } else { self.skip_line() };
+                        match skipped {
+                            Ok(skipped) => {
+                                self.position.offset += skipped;
+                                self.finished = self.stop_on_error;
+                                return Some(Err(error));
+                            },
+                            Err(e) => {
+                                self.finished = true;
+                                return Some(Err(read_error(e, start)));
+                            },
+                        }
+                    }
+                    if self.line.iter().all(|&byte| matches!(byte, b' ' | b'\t' | b'\n' | b'\r')) {
+                        continue;
+                    }
+                    let result: Result<Value,ParseError> = self.parse_line(start);
+                    self.finished = result.is_err() && self.stop_on_error;
+                    return Some(result);
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                },
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(read_error(e, start)));
+                },
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+use types::{Number};
+
+#[test]
+fn values_are_read_one_per_line() {
+    let input: &[u8] = b"{\"a\":1}\n[true]\r\n\n  \n\"x\"";
+    let values: Vec<Value> = NdjsonReader::new(input).map(Result::unwrap).collect();
+    assert_eq!( values, vec![::parsing::parse("{\"a\":1}").unwrap(), ::parsing::parse("[true]").unwrap(), Value::String("x".to_string())] );
+}
+
+#[test]
+fn bad_lines_are_reported_with_their_line_number() {
+    let input: &[u8] = b"1\n{\"a\":}\n\n[2,x]\n3\n";
+    let results: Vec<Result<Value,ParseError>> = NdjsonReader::new(input).collect();
+    assert_eq!( results.len(), 4 );
+    assert_eq!( results[0], Ok(Value::Number(Number::from(1))) );
+    let error: &ParseError = results[1].as_ref().unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnexpectedToken('}') );
+    assert_eq!( error.position(), Position { line: 2, column: 6, offset: 7 } );
+    assert_eq!( results[2].as_ref().unwrap_err().position(), Position { line: 4, column: 4, offset: 13 } );
+    assert_eq!( results[3], Ok(Value::Number(Number::from(3))) );
+}
+
+#[test]
+fn reading_can_stop_at_the_first_error() {
+    let input: &[u8] = b"1\n{\n3\n";
+    let results: Vec<Result<Value,ParseError>> = NdjsonReader::new(input).stop_on_error(true).collect();
+    assert_eq!( results.len(), 2 );
+    assert_eq!( results[1].as_ref().unwrap_err().kind(), &ErrorKind::UnterminatedToken('{') );
+}
+
+#[test]
+fn long_lines_are_cut_off_at_the_input_limit() {
+    let options: ParseOptions = ParseOptions { max_input_bytes: 4, ..ParseOptions::default() };
+    let input: String = format!("1234\n[{}]\n5\n", "6".repeat(10_000));
+    let mut reader: NdjsonReader<io::BufReader<&[u8]>> = NdjsonReader::with_options(io::BufReader::with_capacity(16, input.as_bytes()), options);
+    assert_eq!( reader.next(), Some(Ok(Value::Number(Number::from(1234)))) );
+    let error: ParseError = reader.next().unwrap().unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::InputTooLong(4) );
+    assert_eq!( error.position(), Position { line: 2, column: 5, offset: 9 } );
+    assert_eq!( reader.line.len(), 6 );
+    assert_eq!( reader.next(), Some(Ok(Value::Number(Number::from(5)))) );
+    assert_eq!( reader.next(), None );
+}
+
+#[test]
+fn line_endings_do_not_count_towards_the_input_limit() {
+    let options: ParseOptions = ParseOptions { max_input_bytes: 4, ..ParseOptions::default() };
+    let input: &[u8] = b"1234\r\n5678\n12345\r\n9";
+    let results: Vec<Result<Value,ParseError>> = NdjsonReader::with_options(input, options).collect();
+    assert_eq!( results[0], Ok(Value::Number(Number::from(1234))) );
+    assert_eq!( results[1], Ok(Value::Number(Number::from(5678))) );
+    assert_eq!( results[2].as_ref().unwrap_err().kind(), &ErrorKind::InputTooLong(4) );
+    assert_eq!( results[3], Ok(Value::Number(Number::from(9))) );
+    assert_eq!( results.len(), 4 );
+}
@@ -1,16 +1,23 @@
-use parsing::{Parser, ParseError};
+use parsing::{Parser, ErrorKind};
 use types::{Value};
 
-fn parse(string: &str) -> Result<(),ParseError> {
+#[cfg(test)]
+fn parse(string: &str) -> Result<Value,ErrorKind> {
     let mut parser: NilParser = NilParser::new();
     for ch in string.chars() {
-        try!(parser.push_token(ch))
+        parser.push_token(ch)?
     }
-    Err(ParseError::EmptyStringGiven)
+    parser.get_result()
 }
 
 pub struct NilParser;
 
+impl Default for NilParser {
+    fn default() -> NilParser {
+        NilParser::new()
+    }
+}
+
 impl NilParser {
     pub fn new() -> NilParser {
         NilParser
@@ -18,15 +25,16 @@ impl NilParser {
 }
 
 impl Parser for NilParser {
-    fn push_token(&mut self, ch: char) -> Result<(),ParseError> {
-        Err(ParseError::UnexpectedToken(ch))
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
+        Err(ErrorKind::UnexpectedToken(ch))
     }
-    fn get_result(&self) -> Result<Value, ParseError> {
-        Err(ParseError::EmptyStringGiven) //TODO make these errors better
+    fn get_result(&self) -> Result<Value, ErrorKind> {
+        Err(ErrorKind::UnexpectedEndOfInput)
     }
 }
 
 #[test]
 fn nil_parser_fails_everything() {
-    assert!(parse("").is_err());
+    assert_eq!(parse("").unwrap_err(), ErrorKind::UnexpectedEndOfInput);
+    assert_eq!(parse("x").unwrap_err(), ErrorKind::UnexpectedToken('x'));
 }
@@ -1,5 +1,6 @@
-use parsing::{Parser, ParseError, FromJson};
-use types::{Number, Value};
+use std::str::{FromStr};
+use parsing::{Parser, ErrorKind, ParseError, ParseOptions, FromJson, from_json_as, parse_chars};
+use types::{Decimal, Number, Value};
 
 enum ParseState {
     SquareOne,
@@ -13,56 +14,142 @@ enum ParseState {
     ExponentiationDigitFound,
 }
 
-struct NumberParser {
-    state:  ParseState,
-    buffer: String,
+pub struct NumberParser {
+    options: ParseOptions,
+    state:   ParseState,
+    buffer:  String,
 }
 
 impl FromJson for Number {
-    fn from_json(json_string: &str) -> Result<Box<Number>, ParseError> {
-        let mut parser: NumberParser = NumberParser::new();
-        for ch in json_string.chars() {
-            try!(parser.push_token(ch));
-        }
-        match try!(parser.get_result()) {
-            Value::Number(n) => {
-                Ok(Box::new(n))
-            },
-            _ => {
-                Err(ParseError::EmptyStringGiven) //TODO use better errors
-            }
+    fn from_json(json_string: &str) -> Result<Box<Number>,ParseError> {
+        from_json_as(json_string, &ParseOptions::default(), "number", |value| match value {
+            Value::Number(n) => Ok(n),
+            other => Err(other),
+        })
+    }
+}
+
+impl FromJson for Decimal {
+    fn from_json(json_string: &str) -> Result<Box<Decimal>,ParseError> {
+        let options: ParseOptions = ParseOptions { decimal_numbers: true, ..ParseOptions::default() };
+        from_json_as(json_string, &options, "number", |value| match value {
+            Value::Number(ref n) if n.is_decimal() => Ok(n.as_decimal().unwrap().clone()),
+            other => Err(other),
+        })
+    }
+}
+
+/// Reads the text of a single JSON number, such as `"19.99"`, for decimals that did not come out
+/// of a document. Unlike `from_json`, no whitespace is allowed around it.
+impl FromStr for Decimal {
+    type Err = ParseError;
+
+    fn from_str(literal: &str) -> Result<Decimal,ParseError> {
+        let options: ParseOptions = ParseOptions { decimal_numbers: true, ..ParseOptions::default() };
+        match parse_chars(NumberParser::with_options(options), literal.chars().map(Ok), &options)? {
+            Value::Number(ref n) if n.is_decimal() => Ok(n.as_decimal().unwrap().clone()),
+            other => unreachable!("decimal mode read {:?}", other),
         }
     }
 }
 
+impl Default for NumberParser {
+    fn default() -> NumberParser {
+        NumberParser::new()
+    }
+}
+
 impl NumberParser {
-    fn new() -> NumberParser {
+    pub fn new() -> NumberParser {
+        NumberParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> NumberParser {
         NumberParser {
-            state:  ParseState::SquareOne,
-            buffer: String::new(),
+            options,
+            state:   ParseState::SquareOne,
+            buffer:  String::new(),
+        }
+    }
+
+    /// Integer literals become `u64` or `i64` when they fit. `-0` stays a float so its sign
+    /// survives.
+    fn integer(&self) -> Number {
+        let parsed: Option<Number> = if self.buffer.starts_with('-') {
+            self.buffer.parse::<i64>().ok().filter(|&n| n != 0).map(Number::from)
+        } else {
+            self.buffer.parse::<u64>().ok().map(Number::from)
+        };
+        match parsed {
+            Some(n) => n,
+            None if self.options.arbitrary_precision && self.buffer != "-0" => {
+                Number::verbatim(self.buffer.clone())
+            },
+            None => self.float(),
+        }
+    }
+
+    /// Literals beyond the range of an `f64` are kept verbatim rather than becoming infinite, as
+    /// are, with `arbitrary_precision`, those that would not read back the same.
+    fn float(&self) -> Number {
+        let n: f64 = self.buffer.parse::<f64>().unwrap();
+        if !n.is_finite() || (self.options.arbitrary_precision && !round_trips(&self.buffer, n)) {
+            Number::verbatim(self.buffer.clone())
+        } else {
+            Number::from(n)
+        }
+    }
+
+    /// A character that could be part of a number makes the literal invalid, since nothing in
+    /// JSON may follow a number with one. Anything else may just mean the number has ended.
+    fn reject(&self, ch: char) -> ErrorKind {
+        match ch {
+            '0'..='9' | '.' | 'e' | 'E' | '+' | '-' => {
+                let mut literal: String = self.buffer.clone();
+                literal.push(ch);
+                ErrorKind::InvalidNumber(literal)
+            },
+            _ => {
+                ErrorKind::UnexpectedToken(ch)
+            },
         }
     }
 }
 
+/// Whether `n` is printed back as the same number that `literal` spells out.
+fn round_trips(literal: &str, n: f64) -> bool {
+    match (Decimal::from_literal(literal.to_string()), Decimal::from_literal(format!("{:e}", n))) {
+        (Some(written), Some(read)) => written.normalized() == read.normalized(),
+        _                           => false,
+    }
+}
+
 impl Parser for NumberParser {
-    fn get_result(&self) -> Result<Value,ParseError> {
+    fn get_result(&self) -> Result<Value,ErrorKind> {
         match self.state {
-            ParseState::SquareOne => { Err(ParseError::EmptyStringGiven) }, 
+            ParseState::SquareOne => { Err(ErrorKind::EmptyInput) }, 
             ParseState::NegativeFound 
             | ParseState::DecimalFound 
             | ParseState::ExponentiationFound 
             | ParseState::SignedExponentiationFound => {
-                Err(ParseError::UnexpectedToken(self.buffer.chars().last().unwrap()))
+                Err(ErrorKind::InvalidNumber(self.buffer.clone()))
+            },
+            _ if self.options.decimal_numbers => {
+                match Decimal::from_literal(self.buffer.clone()) {
+                    Some(d) => Ok(Value::Number(Number::from(d))),
+                    None    => Err(ErrorKind::ExponentOutOfRange(self.buffer.clone())),
+                }
             },
             ParseState::FirstDigitZero
-            | ParseState::DigitsLeftOfDecimal 
-            | ParseState::DigitsRightOfDecimal 
+            | ParseState::DigitsLeftOfDecimal => {
+                Ok(Value::Number(self.integer()))
+            },
+            ParseState::DigitsRightOfDecimal 
             | ParseState::ExponentiationDigitFound => {
-                Ok(Value::Number(self.buffer.parse::<Number>().unwrap()))
+                Ok(Value::Number(self.float()))
             },
         }
     }
-    fn push_token(&mut self, ch: char) -> Result<(),ParseError> {
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
         match self.state {
             ParseState::SquareOne => {
                 match ch {
@@ -72,11 +159,11 @@ impl Parser for NumberParser {
                     '0' => {
                         self.state = ParseState::FirstDigitZero;
                     },
-                    '1'...'9' => {
+                    '1'..='9' => {
                         self.state = ParseState::DigitsLeftOfDecimal;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     }
                 }
             },
@@ -88,11 +175,11 @@ impl Parser for NumberParser {
                     'e' | 'E' => {
                         self.state = ParseState::ExponentiationFound;
                     },
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::DigitsLeftOfDecimal;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     }
                 }
             },
@@ -105,30 +192,30 @@ impl Parser for NumberParser {
                         self.state = ParseState::ExponentiationFound;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     }
                 }
             },
             ParseState::DecimalFound => {
                 match ch {
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::DigitsRightOfDecimal;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
             ParseState::DigitsRightOfDecimal => {
                 match ch {
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::DigitsRightOfDecimal;
                     },
                     'e' | 'E' => {
                         self.state = ParseState::ExponentiationFound;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
@@ -137,11 +224,11 @@ impl Parser for NumberParser {
                     '0' => {
                         self.state = ParseState::FirstDigitZero;
                     },
-                    '1'...'9' => {
+                    '1'..='9' => {
                         self.state = ParseState::DigitsLeftOfDecimal;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
@@ -150,51 +237,146 @@ impl Parser for NumberParser {
                     '-' | '+' => {
                         self.state = ParseState::SignedExponentiationFound;
                     },
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::ExponentiationDigitFound;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
             ParseState::SignedExponentiationFound => {
                 match ch {
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::ExponentiationDigitFound;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
             ParseState::ExponentiationDigitFound => {
                 match ch {
-                    '0'...'9' => {
+                    '0'..='9' => {
                         self.state = ParseState::ExponentiationDigitFound;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(self.reject(ch));
                     },
                 }
             },
         }
+        if self.buffer.len() >= self.options.max_number_length {
+            return Err(ErrorKind::NumberTooLong(self.options.max_number_length));
+        }
         self.buffer.push(ch);
         Ok(())
     }
 }
 
+#[cfg(test)]
+use encoding::{ToJson};
 
 #[test]
 fn valid_json_numbers_pass() {
-    assert_eq!( *Number::from_json("0").unwrap(), 0 as Number );
-    assert_eq!( *Number::from_json("0.1").unwrap(), 0.1 as Number );
-    assert_eq!( *Number::from_json("-32").unwrap(), -32 as Number );
-    assert_eq!( *Number::from_json("4.5e1").unwrap(), 45 as Number );
-    assert_eq!( *Number::from_json("3E2").unwrap(), 300 as Number );
-    assert_eq!( *Number::from_json("5e-2").unwrap(), 0.05 as Number );
-    assert_eq!( *Number::from_json("6E-1").unwrap(), 0.6 as Number );
-    assert_eq!( *Number::from_json("3e+3").unwrap(), 3000 as Number );
+    assert_eq!( *Number::from_json("0").unwrap(), Number::from(0) );
+    assert_eq!( *Number::from_json("0.1").unwrap(), Number::from(0.1) );
+    assert_eq!( *Number::from_json("-32").unwrap(), Number::from(-32) );
+    assert_eq!( *Number::from_json("4.5e1").unwrap(), Number::from(45.0) );
+    assert_eq!( *Number::from_json("3E2").unwrap(), Number::from(300.0) );
+    assert_eq!( *Number::from_json("5e-2").unwrap(), Number::from(0.05) );
+    assert_eq!( *Number::from_json("6E-1").unwrap(), Number::from(0.6) );
+    assert_eq!( *Number::from_json("3e+3").unwrap(), Number::from(3000.0) );
+}
+
+#[test]
+fn encoded_floats_parse_back_to_the_same_value() {
+    let floats: [f64; 10] = [0.1, 1.0 / 3.0, 1e300, -1e-300, 5e-324, f64::MAX, f64::MIN_POSITIVE, 123456.789, 1e21, 2.5e-7];
+    for &n in floats.iter() {
+        let encoded: String = Number::from(n).to_string();
+        assert_eq!( Number::from_json(&encoded).unwrap().as_f64().to_bits(), n.to_bits(), "{}", encoded );
+    }
+}
+
+#[test]
+fn integers_keep_full_precision() {
+    assert_eq!( Number::from_json("9007199254740993").unwrap().as_u64(), Some(9007199254740993) );
+    assert_eq!( Number::from_json("18446744073709551615").unwrap().as_u64(), Some(u64::MAX) );
+    assert_eq!( Number::from_json("-9223372036854775808").unwrap().as_i64(), Some(i64::MIN) );
+    assert!( Number::from_json("-0").unwrap().is_f64() );
+    assert!( Number::from_json("1.0").unwrap().is_f64() );
+}
+
+#[test]
+fn huge_integers_are_kept_verbatim_with_arbitrary_precision() {
+    let options: ParseOptions = ParseOptions { arbitrary_precision: true, ..ParseOptions::default() };
+    let huge: &str = "123456789012345678901234567890";
+    match ::parsing::parse_with_options(huge, &options).unwrap() {
+        Value::Number(n) => assert_eq!( n.as_str(), Some(huge) ),
+        other            => panic!("expected a number, got {:?}", other),
+    }
+    assert!( Number::from_json(huge).unwrap().is_f64() );
+}
+
+#[test]
+fn decimals_can_be_read_from_plain_text() {
+    let price: Decimal = "19.99".parse::<Decimal>().unwrap();
+    assert_eq!( (price.mantissa(), price.exponent()), (Some(1999), -2) );
+    assert_eq!( Number::from(price).to_string(), "19.99" );
+    assert_eq!( "-1.5E+3".parse::<Decimal>().unwrap().as_str(), "-1.5E+3" );
+
+    assert_eq!( "01".parse::<Decimal>().unwrap_err().kind(), &ErrorKind::InvalidNumber("01".to_string()) );
+    assert_eq!( "1.".parse::<Decimal>().unwrap_err().kind(), &ErrorKind::InvalidNumber("1.".to_string()) );
+    assert_eq!( " 1".parse::<Decimal>().unwrap_err().kind(), &ErrorKind::UnexpectedToken(' ') );
+    assert_eq!( "1 ".parse::<Decimal>().unwrap_err().kind(), &ErrorKind::TrailingCharacters(' ') );
+    assert_eq!( "".parse::<Decimal>().unwrap_err().kind(), &ErrorKind::EmptyInput );
+}
+
+#[test]
+fn decimal_exponents_beyond_i64_are_out_of_range() {
+    let huge: &str = "1e99999999999999999999";
+    assert_eq!( Decimal::from_json(huge).unwrap_err().kind(), &ErrorKind::ExponentOutOfRange(huge.to_string()) );
+    assert_eq!( huge.parse::<Decimal>().unwrap_err().kind(), &ErrorKind::ExponentOutOfRange(huge.to_string()) );
+    assert!( Number::from_json(huge).is_ok() );
+}
+
+#[test]
+fn precise_decimals_are_kept_verbatim_with_arbitrary_precision() {
+    let options: ParseOptions = ParseOptions { arbitrary_precision: true, ..ParseOptions::default() };
+    let number = |literal: &str| match ::parsing::parse_with_options(literal, &options).unwrap() {
+        Value::Number(n) => n,
+        other            => panic!("expected a number, got {:?}", other),
+    };
+    assert_eq!( number("0.1000000000000000000000001").as_str(), Some("0.1000000000000000000000001") );
+    assert_eq!( number("-2.5000000000000000000000001e-3").as_str(), Some("-2.5000000000000000000000001e-3") );
+    assert!( number("0.1").is_f64() );
+    assert!( number("-1.250E+2").is_f64() );
+    assert!( number("0.0").is_f64() );
+}
+
+#[test]
+fn numbers_beyond_f64_are_never_infinite() {
+    assert_eq!( Number::from_json("1e400").unwrap().as_str(), Some("1e400") );
+    assert_eq!( Number::from_json("-1.5E+999").unwrap().as_str(), Some("-1.5E+999") );
+    assert!( Number::from_json("1e400").unwrap().is_finite() );
+    assert_eq!( Number::from_json("1e-400").unwrap().as_f64(), 0.0 );
+
+    let options: ParseOptions = ParseOptions { arbitrary_precision: true, ..ParseOptions::default() };
+    assert_eq!( ::parsing::parse_with_options("[1e400]", &options).unwrap().to_json(), "[1e400]" );
+}
+
+#[test]
+fn decimal_mode_keeps_the_exact_text() {
+    let price: Decimal = *Decimal::from_json("0.10").unwrap();
+    assert_eq!( price.as_str(), "0.10" );
+    assert_eq!( (price.mantissa(), price.exponent()), (Some(10), -2) );
+    assert_eq!( Decimal::from_json("100").unwrap().as_str(), "100" );
+
+    let options: ParseOptions = ParseOptions { decimal_numbers: true, ..ParseOptions::default() };
+    match ::parsing::parse_with_options("0.1", &options).unwrap() {
+        Value::Number(n) => assert_eq!( n.as_decimal().map(|d| d.digits()), Some("1") ),
+        other            => panic!("expected a number, got {:?}", other),
+    }
 }
 
 #[test]
@@ -205,3 +387,19 @@ fn invalid_json_numbers_fail() {
     assert!( Number::from_json("0.").is_err() );
     assert!( Number::from_json("1.2e1.0").is_err() );
 }
+
+#[test]
+fn invalid_json_numbers_report_the_literal() {
+    assert_eq!( Number::from_json("0.").unwrap_err().kind(), &ErrorKind::InvalidNumber("0.".to_string()) );
+    assert_eq!( Number::from_json("01").unwrap_err().kind(), &ErrorKind::InvalidNumber("01".to_string()) );
+    assert_eq!( Number::from_json("-").unwrap_err().kind(), &ErrorKind::InvalidNumber("-".to_string()) );
+    assert_eq!( Number::from_json("1e+").unwrap_err().kind(), &ErrorKind::InvalidNumber("1e+".to_string()) );
+    assert_eq!( ::parsing::parse("[1.5.2]").unwrap_err().kind(), &ErrorKind::InvalidNumber("1.5.".to_string()) );
+    assert_eq!( ::parsing::parse("[1x]").unwrap_err().kind(), &ErrorKind::UnexpectedToken('x') );
+}
+
+#[test]
+fn non_numbers_are_a_type_mismatch() {
+    assert_eq!( Number::from_json("\"1\"").unwrap_err().kind(),
+                &ErrorKind::TypeMismatch { expected: "number", found: "string" } );
+}
@@ -1,79 +1,134 @@
-use parsing::{Parser, ParseError};
-use parsing::string::{StringParser};
-use parsing::nil::{NilParser};
+use parsing::{Parser, ErrorKind, ParseError, ParseOptions, FromJson, from_json_as};
+use parsing::value::{ValueParser};
 use types::{Object, Value};
 
-enum ParseState {
-    SquareOne,
-    ExpectingStringKeyName,
+/// Parses a JSON object and nothing else: the first character must open it, and nothing may
+/// follow its closing brace.
+pub struct ObjectParser {
+    parser:  ValueParser,
+    started: bool,
 }
 
-struct ObjectParser {
-    object:       Object,
-    state:        ParseState,
-    sub_parser:   Box<Parser>,
-    current_pair: (Option<String>,Option<Value>),
+impl FromJson for Object {
+    fn from_json(json_string: &str) -> Result<Box<Object>,ParseError> {
+        from_json_as(json_string, &ParseOptions::default(), "object", |value| match value {
+            Value::Object(o) => Ok(o),
+            other => Err(other),
+        })
+    }
 }
 
+#[cfg(test)]
 fn parse(json_string: &str) -> Result<Object,ParseError> {
-    let mut parser: ObjectParser = ObjectParser::new();
-    for ch in json_string.chars() {
-        try!(parser.push_token(ch));
+    Object::from_json(json_string).map(|object| *object)
+}
+
+impl Default for ObjectParser {
+    fn default() -> ObjectParser {
+        ObjectParser::new()
     }
-    Ok(Object::new())
 }
 
 impl ObjectParser {
-    fn new() -> ObjectParser {
+    pub fn new() -> ObjectParser {
+        ObjectParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> ObjectParser {
         ObjectParser {
-            object:       Object::new(),
-            state:        ParseState::SquareOne,
-            sub_parser:   Box::new(NilParser::new()),
-            current_pair: (None,None),
+            parser:  ValueParser::with_options(options),
+            started: false,
         }
     }
 }
 
 impl Parser for ObjectParser {
-    fn get_result(&self) -> Result<Value, ParseError> {
-        Ok(Value::Object(Object::new()))
+    fn get_result(&self) -> Result<Value, ErrorKind> {
+        self.parser.get_result()
+    }
+    fn is_complete(&self) -> bool {
+        self.parser.is_complete()
+    }
+    fn has_result(&self) -> bool {
+        self.parser.has_result()
+    }
+    fn take_result(&mut self) -> Result<Value, ErrorKind> {
+        self.parser.take_result()
     }
-    fn push_token(&mut self, ch: char) -> Result<(),ParseError> {
-        match self.state {
-            ParseState::SquareOne => {
-                match ch {
-                    '{' => {
-                        self.state      = ParseState::ExpectingStringKeyName;
-                        self.sub_parser = Box::new(StringParser::new());
-                    },
-                    _   => { 
-                        return Err(ParseError::UnexpectedToken(ch));
-                    },
-                }
-            },
-            ParseState::ExpectingStringKeyName => {
-                match ch {
-                    ':' => {
-                        match self.sub_parser.push_token(ch) {
-                            Ok(_) => {},
-                            Err(_) => {
-                                //self.current_pair.0 = self.sub_parser.get_result(); //TODO fix
-                                //maybe i can make method like unwrap_string() or unwrap_number??
-                            },
-                        }
-                    },
-                    _ => {
-                        try!(self.sub_parser.push_token(ch));
-                    },
-                }
-            },
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
+        if (!self.started && ch != '{') || self.parser.is_complete() {
+            return Err(ErrorKind::UnexpectedToken(ch));
         }
-        Ok(())
+        self.started = true;
+        self.parser.push_token(ch)
+    }
+}
+
+// HAPPY PATHS
+#[test]
+fn valid_objects_pass() {
+    assert_eq!( parse("{}").unwrap(), Object::new() );
+    assert_eq!( parse("{ \n }").unwrap(), Object::new() );
+
+    let object: Object = parse("{\"a\":1,\"b\":\"two\"}").unwrap();
+    assert_eq!( object.len(), 2 );
+    assert_eq!( object["a"], Value::Number(::types::Number::from(1)) );
+    assert_eq!( object["b"], Value::String("two".to_string()) );
+}
+
+#[test]
+fn objects_with_whitespace_between_tokens_pass() {
+    let object: Object = parse("{ \"a\" : 1 ,\n\t\"b\" : 2 }").unwrap();
+    assert_eq!( object["a"], Value::Number(::types::Number::from(1)) );
+    assert_eq!( object["b"], Value::Number(::types::Number::from(2)) );
+}
+
+#[test]
+fn nested_objects_pass() {
+    let object: Object = parse("{\"a\":{\"b\":{}}}").unwrap();
+    let mut inner: Object = Object::new();
+    inner.insert("b".to_string(), Value::Object(Object::new()));
+    assert_eq!( object["a"], Value::Object(inner) );
+}
+
+#[test]
+fn finished_objects_are_handed_over() {
+    let mut parser: ObjectParser = ObjectParser::new();
+    for ch in "{\"a\":[1]}".chars() {
+        parser.push_token(ch).unwrap();
     }
+    assert_eq!( parser.take_result().unwrap(), ::parsing::parse("{\"a\":[1]}").unwrap() );
+    assert_eq!( parser.take_result(), Err(ErrorKind::UnexpectedEndOfInput) );
+    assert_eq!( parser.get_result(), Err(ErrorKind::UnexpectedEndOfInput) );
+}
+
+#[test]
+fn duplicate_keys_keep_the_last_value() {
+    assert_eq!( parse("{\"a\":1,\"a\":2}").unwrap()["a"], Value::Number(::types::Number::from(2)) );
 }
 
+// SAD PATHS
 #[test]
 fn invalid_objects_fail() {
     assert!( parse("[").is_err() );
     assert!( parse("{a").is_err() );
+    assert!( parse("{").is_err() );
+    assert!( parse("{\"a\"").is_err() );
+    assert!( parse("{\"a\":1").is_err() );
+    assert!( parse("{\"a\":1}}").is_err() );
+    assert!( parse("{1:1}").is_err() );
+}
+
+#[test]
+fn duplicate_separators_fail() {
+    assert_eq!( parse("{\"a\"::1}").unwrap_err().kind(), &ErrorKind::UnexpectedToken(':') );
+    assert_eq!( parse("{\"a\":1,,\"b\":2}").unwrap_err().kind(), &ErrorKind::UnexpectedToken(',') );
+    assert_eq!( parse("{,}").unwrap_err().kind(), &ErrorKind::UnexpectedToken(',') );
+    assert_eq!( parse("{\"a\":1,}").unwrap_err().kind(), &ErrorKind::UnexpectedToken('}') );
+}
+
+#[test]
+fn missing_values_fail() {
+    assert_eq!( parse("{\"a\":}").unwrap_err().kind(), &ErrorKind::UnexpectedToken('}') );
+    assert_eq!( parse("{\"a\"}").unwrap_err().kind(), &ErrorKind::UnexpectedToken('}') );
+    assert_eq!( parse("{\"a\":1 \"b\":2}").unwrap_err().kind(), &ErrorKind::UnexpectedToken('"') );
 }
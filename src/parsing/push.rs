@@ -0,0 +1,138 @@
+use parsing::{ParseError, ParseOptions, Parser, Tracker, SNIPPET_CONTEXT};
+use parsing::utf8::{Utf8Chars, Utf8Decoder};
+use parsing::value::{ValueParser};
+use types::{Value};
+
+/// Whether a `PushParser` has seen a whole value yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PushStatus {
+    /// The value is not finished; keep pushing.
+    NeedMoreInput,
+    /// The value is finished and only whitespace may follow. Call `finish` to take it.
+    Complete,
+}
+
+/// Parses one JSON document fed in byte chunks as they arrive, e.g. from a socket. Chunks may
+/// split characters and tokens anywhere.
+///
+/// A number at the top level can always grow by another digit, so it is only `Complete` once
+/// something follows it; otherwise `finish` is what ends it.
+pub struct PushParser {
+    parser:  ValueParser,
+    tracker: Tracker,
+    decoder: Utf8Decoder,
+    error:   Option<ParseError>,
+}
+
+impl Default for PushParser {
+    fn default() -> PushParser {
+        PushParser::new()
+    }
+}
+
+impl PushParser {
+    pub fn new() -> PushParser {
+        PushParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> PushParser {
+        PushParser {
+            parser:  ValueParser::with_options(options),
+            tracker: Tracker::new(&options),
+            decoder: Utf8Decoder::new(),
+            error:   None,
+        }
+    }
+
+    /// Feeds the next chunk of input. Once this fails, every later call fails the same way.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<PushStatus,ParseError> {
+        if let Some(ref error) = self.error {
+            return Err(error.clone());
+        }
+        for (i, &byte) in chunk.iter().enumerate() {
+            let ch: char = match self.decoder.push(byte) {
+                Ok(Some(ch)) => ch,
+                Ok(None)     => continue,
+                Err(kind)    => return Err(self.fail(self.tracker.error(kind, None))),
+            };
+            if let Err(mut error) = self.tracker.push_token(&mut self.parser, ch) {
+                let following = Utf8Chars::new(chunk[i + 1..].iter().map(|&byte| Ok(byte)));
+                error.snippet.extend(following.take(SNIPPET_CONTEXT).map_while(Result::ok));
+                return Err(self.fail(error));
+            }
+        }
+        Ok(self.status())
+    }
+
+    pub fn status(&self) -> PushStatus {
+        if self.parser.is_complete() {
+            PushStatus::Complete
+        } else {
+            PushStatus::NeedMoreInput
+        }
+    }
+
+    /// Signals the end of the input and hands back the value.
+    pub fn finish(mut self) -> Result<Value,ParseError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if let Err(kind) = self.decoder.finish() {
+            return Err(self.tracker.error(kind, None));
+        }
+        self.tracker.get_result(&mut self.parser)
+    }
+
+    fn fail(&mut self, error: ParseError) -> ParseError {
+        self.error = Some(error.clone());
+        error
+    }
+}
+
+#[cfg(test)]
+use parsing::{ErrorKind};
+
+#[test]
+fn chunks_can_split_anywhere() {
+    let json: &str = "{\"name\":\"Zoë 😀\",\"tags\":[true,null,-1.5e3],\"n\":{}}";
+    let expected: Value = ::parsing::parse(json).unwrap();
+    for size in 1..8 {
+        let mut parser: PushParser = PushParser::new();
+        for chunk in json.as_bytes().chunks(size) {
+            assert_eq!( parser.status(), PushStatus::NeedMoreInput );
+            parser.push(chunk).unwrap();
+        }
+        assert_eq!( parser.status(), PushStatus::Complete );
+        assert_eq!( parser.finish().unwrap(), expected );
+    }
+}
+
+#[test]
+fn numbers_complete_once_something_follows() {
+    let mut parser: PushParser = PushParser::new();
+    assert_eq!( parser.push(b" 12").unwrap(), PushStatus::NeedMoreInput );
+    assert_eq!( parser.push(b"3").unwrap(), PushStatus::NeedMoreInput );
+    assert_eq!( parser.push(b"\n").unwrap(), PushStatus::Complete );
+    assert_eq!( parser.finish().unwrap(), Value::Number(::types::Number::from(123)) );
+
+    let mut parser: PushParser = PushParser::new();
+    parser.push(b"-0.5").unwrap();
+    assert_eq!( parser.finish().unwrap(), Value::Number(::types::Number::from(-0.5)) );
+}
+
+#[test]
+fn errors_stick() {
+    let mut parser: PushParser = PushParser::new();
+    parser.push(b"[1,").unwrap();
+    let error: ParseError = parser.push(b"]").unwrap_err();
+    assert_eq!( error.kind(), &ErrorKind::UnexpectedToken(']') );
+    assert_eq!( error.position().offset, 3 );
+    assert_eq!( parser.push(b"2]").unwrap_err(), error );
+    assert_eq!( parser.finish().unwrap_err(), error );
+
+    let mut parser: PushParser = PushParser::new();
+    parser.push(b"\"\xe2\x82").unwrap();
+    assert_eq!( parser.finish().unwrap_err().kind(), &ErrorKind::UnexpectedEndOfInput );
+
+    let mut parser: PushParser = PushParser::new();
+    assert_eq!( parser.push(b"{} {").unwrap_err().kind(), &ErrorKind::TrailingCharacters('{') );
+}
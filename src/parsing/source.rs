@@ -0,0 +1,61 @@
+use std::io;
+use parsing::{ErrorKind, ParseOptions, Tracker};
+use parsing::utf8::{ReadBytes, Utf8Chars};
+
+/// The input of a reader that works through it one character at a time with one character of
+/// lookahead, along with where it has got to.
+pub(crate) struct Source<'a> {
+    chars:              Box<dyn Iterator<Item = Result<char,ErrorKind>> + 'a>,
+    /// A character read from the input but not yet consumed.
+    peeked:             Option<char>,
+    pub(crate) tracker: Tracker,
+}
+
+impl<'a> Source<'a> {
+    pub(crate) fn new(json_string: &'a str) -> Source<'a> {
+        Source::from_chars(Box::new(json_string.chars().map(Ok)))
+    }
+    pub(crate) fn from_slice(bytes: &'a [u8]) -> Source<'a> {
+        Source::from_chars(Box::new(Utf8Chars::new(bytes.iter().map(|&byte| Ok(byte)))))
+    }
+    pub(crate) fn from_reader<R: io::Read + 'a>(reader: R) -> Source<'a> {
+        Source::from_chars(Box::new(Utf8Chars::new(ReadBytes::new(reader))))
+    }
+    fn from_chars(chars: Box<dyn Iterator<Item = Result<char,ErrorKind>> + 'a>) -> Source<'a> {
+        Source {
+            chars,
+            peeked:  None,
+            tracker: Tracker::new(&ParseOptions::default()),
+        }
+    }
+
+    /// Starts tracking afresh under `options`.
+    pub(crate) fn set_options(&mut self, options: &ParseOptions) {
+        self.tracker = Tracker::new(options);
+    }
+
+    pub(crate) fn peek(&mut self) -> Option<Result<char,ErrorKind>> {
+        if self.peeked.is_none() {
+            match self.chars.next()? {
+                Ok(ch) => self.peeked = Some(ch),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.peeked.map(Ok)
+    }
+
+    pub(crate) fn consume(&mut self, ch: char) {
+        self.peeked = None;
+        self.tracker.advance(ch);
+    }
+
+    /// Steps over `ch` if it is a byte order mark to be skipped.
+    pub(crate) fn skip_bom(&mut self, ch: char) -> bool {
+        if self.tracker.skip_bom(ch) {
+            self.peeked = None;
+            true
+        } else {
+            false
+        }
+    }
+}
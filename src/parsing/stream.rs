@@ -0,0 +1,206 @@
+use std::io;
+use parsing::{ErrorKind, ParseError, ParseOptions, Parser, is_whitespace};
+use parsing::source::{Source};
+use parsing::value::{ValueParser};
+use types::{Value};
+
+/// The record separator that opens each text of an RFC 7464 JSON text sequence.
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// Reads values written back to back, like `{"a":1}{"b":2}` or `1 2 [3]`, yielding each with the
+/// byte offset just past its end. Whitespace between values is skipped; numbers and literals
+/// need some to tell where they stop.
+///
+/// With `record_separators` set it reads RFC 7464 JSON text sequences instead, where each value
+/// is introduced by U+001E. A value cut short by the next separator is reported and reading
+/// picks up again after it; otherwise the first error ends the iteration.
+pub struct StreamReader<'a> {
+    source:            Source<'a>,
+    options:           ParseOptions,
+    record_separators: bool,
+    finished:          bool,
+}
+
+impl<'a> StreamReader<'a> {
+    pub fn new(json_string: &'a str) -> StreamReader<'a> {
+        StreamReader::from_source(Source::new(json_string))
+    }
+    pub fn from_slice(bytes: &'a [u8]) -> StreamReader<'a> {
+        StreamReader::from_source(Source::from_slice(bytes))
+    }
+    pub fn from_reader<R: io::Read + 'a>(reader: R) -> StreamReader<'a> {
+        StreamReader::from_source(Source::from_reader(reader))
+    }
+    fn from_source(source: Source<'a>) -> StreamReader<'a> {
+        StreamReader {
+            source,
+            options:           ParseOptions::default(),
+            record_separators: false,
+            finished:          false,
+        }
+    }
+
+    /// Reads with `options` instead of the defaults. Only takes effect before the first value.
+    pub fn with_options(mut self, options: ParseOptions) -> StreamReader<'a> {
+        self.options = options;
+        self.source.set_options(&options);
+        self
+    }
+
+    pub fn record_separators(mut self, record_separators: bool) -> StreamReader<'a> {
+        self.record_separators = record_separators;
+        self
+    }
+
+    fn is_separator(&self, ch: char) -> bool {
+        is_whitespace(ch) || (self.record_separators && ch == RECORD_SEPARATOR)
+    }
+
+    /// Ends the iteration, unless this is a text sequence and a later text can still be read.
+    fn fail(&mut self, error: ParseError) -> Option<Result<(Value,usize),ParseError>> {
+        if !self.record_separators {
+            self.finished = true;
+            return Some(Err(error));
+        }
+        // The bad text is skipped no further than the input limit allows.
+        while let Some(Ok(ch)) = self.source.peek() {
+            if self.source.tracker.check_length(ch).is_err() {
+                break;
+            }
+            if ch == RECORD_SEPARATOR {
+                return Some(Err(error));
+            }
+            self.source.consume(ch);
+        }
+        self.finished = true;
+        Some(Err(error))
+    }
+
+    fn finished_value(&self, value: Value) -> Option<Result<(Value,usize),ParseError>> {
+        Some(Ok((value, self.source.tracker.position.offset)))
+    }
+}
+
+impl<'a> Iterator for StreamReader<'a> {
+    type Item = Result<(Value,usize),ParseError>;
+
+    fn next(&mut self) -> Option<Result<(Value,usize),ParseError>> {
+        if self.finished {
+            return None;
+        }
+        self.source.tracker.restart_count();
+        let mut parser: ValueParser = ValueParser::with_options(self.options);
+        let mut started: bool = false;
+        loop {
+            let ch: char = match self.source.peek() {
+                None if !started => {
+                    self.finished = true;
+                    return None;
+                },
+                None => {
+                    return match self.source.tracker.get_result(&mut parser) {
+                        Ok(value)  => self.finished_value(value),
+                        Err(error) => self.fail(error),
+                    };
+                },
+                Some(Err(kind)) => {
+                    let error: ParseError = self.source.tracker.error(kind, None);
+                    self.finished = true;
+                    return Some(Err(error));
+                },
+                Some(Ok(ch)) => ch,
+            };
+            if let Err(error) = self.source.tracker.check_length(ch) {
+                self.finished = true;
+                return Some(Err(error));
+            }
+            if self.source.skip_bom(ch) {
+                continue;
+            }
+            if self.is_separator(ch) {
+                if !started {
+                    self.source.consume(ch);
+                    continue;
+                }
+                if let Ok(value) = parser.take_result() {
+                    return self.finished_value(value);
+                }
+            }
+            started = true;
+            match parser.push_token(ch) {
+                Ok(_) => {
+                    self.source.consume(ch);
+                    if parser.is_complete() {
+                        return self.finished_value(parser.take_result().unwrap());
+                    }
+                },
+                Err(ErrorKind::UnexpectedToken(_)) if parser.has_result() => {
+                    return self.finished_value(parser.take_result().unwrap());
+                },
+                Err(kind) => {
+                    let error: ParseError = self.source.tracker.error(kind, Some(ch));
+                    if ch != RECORD_SEPARATOR {
+                        self.source.consume(ch);
+                    }
+                    return self.fail(error);
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn values(reader: StreamReader) -> Vec<Result<(Value,usize),ErrorKind>> {
+    reader.map(|result| result.map_err(|error| error.kind().clone())).collect()
+}
+
+#[cfg(test)]
+fn value(json_string: &str) -> Value {
+    ::parsing::parse(json_string).unwrap()
+}
+
+#[test]
+fn back_to_back_values_are_split() {
+    assert_eq!( values(StreamReader::new("{\"a\":1}{\"b\":2}")), vec![Ok((value("{\"a\":1}"), 7)), Ok((value("{\"b\":2}"), 14))] );
+    assert_eq!( values(StreamReader::new(" 1 2\n[3]\"x\"true-4 ")),
+                vec![Ok((value("1"), 2)), Ok((value("2"), 4)), Ok((value("[3]"), 8)), Ok((value("\"x\""), 11)),
+                     Ok((value("true"), 15)), Ok((value("-4"), 17))] );
+    assert_eq!( values(StreamReader::new("  ")), vec![] );
+    assert_eq!( values(StreamReader::from_reader("[1][\"é\"]".as_bytes())), vec![Ok((value("[1]"), 3)), Ok((value("[\"é\"]"), 9))] );
+}
+
+#[test]
+fn the_first_error_ends_a_plain_stream() {
+    assert_eq!( values(StreamReader::new("[1] ]2")), vec![Ok((value("[1]"), 3)), Err(ErrorKind::UnexpectedToken(']'))] );
+    assert_eq!( values(StreamReader::new("{}{")), vec![Ok((value("{}"), 2)), Err(ErrorKind::UnterminatedToken('{'))] );
+}
+
+#[test]
+fn record_separated_sequences_recover_from_bad_texts() {
+    let input: &str = "\u{1e}{\"a\":1}\n\u{1e}[1,\n\u{1e}2\n\u{1e}tru\u{1e}\"s\"\n";
+    let reader: StreamReader = StreamReader::new(input).record_separators(true);
+    assert_eq!( values(reader), vec![
+        Ok((value("{\"a\":1}"), 8)),
+        Err(ErrorKind::UnexpectedToken('\u{1e}')),
+        Ok((value("2"), 16)),
+        Err(ErrorKind::UnexpectedToken('\u{1e}')),
+        Ok((value("\"s\""), 25)),
+    ] );
+    assert_eq!( values(StreamReader::new("1\u{1e}2")), vec![Ok((value("1"), 1)), Err(ErrorKind::UnexpectedToken('\u{1e}'))] );
+}
+
+#[test]
+fn the_input_limit_covers_each_value() {
+    let options: ParseOptions = ParseOptions { max_input_bytes: 4, ..ParseOptions::default() };
+    let input: String = "[1] ".repeat(100);
+    assert_eq!( values(StreamReader::new(&input).with_options(options)).len(), 100 );
+    assert_eq!( values(StreamReader::new("[1] [22] [3]").with_options(options)), vec![Ok((value("[1]"), 3)), Err(ErrorKind::InputTooLong(4))] );
+}
+
+#[test]
+fn skipping_a_bad_text_stops_at_the_input_limit() {
+    let options: ParseOptions = ParseOptions { max_input_bytes: 4, ..ParseOptions::default() };
+    let input: String = format!("\u{1e}x{}\u{1e}1", "y".repeat(1000));
+    let reader: StreamReader = StreamReader::new(&input).with_options(options).record_separators(true);
+    assert_eq!( values(reader), vec![Err(ErrorKind::UnexpectedToken('x'))] );
+}
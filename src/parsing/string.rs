@@ -1,11 +1,14 @@
 use std::char;
-use parsing::{Parser,ParseError, FromJson};
+use std::mem;
+use parsing::{Parser, ErrorKind, ParseError, ParseOptions, FromJson, from_json_as};
 use types::{Value};
 
 pub struct StringParser {
-    buffer:     String,
-    hex_string: String,
-    state:      ParseState,
+    options:        ParseOptions,
+    buffer:         String,
+    hex_string:     String,
+    high_surrogate: Option<u32>,
+    state:          ParseState,
 }
 
 enum ParseState {
@@ -14,46 +17,104 @@ enum ParseState {
     EscapeCharFound,
     ExpectingEndOfString,
     HexDigitExpected(u8),
+    LowSurrogateEscapeExpected,
+    LowSurrogateUExpected,
 }
 
 impl FromJson for String {
     fn from_json(json_string: &str) -> Result<Box<String>,ParseError> {
-        let mut parser: StringParser = StringParser::new();
-        for ch in json_string.chars() {
-            try!(parser.push_token(ch))
-        }
-        match try!(parser.get_result()) {
-            Value::String(s) => {
-                Ok(Box::new(s))
-            },
-            _ => {
-                Err(ParseError::EmptyStringGiven) //TODO use better errors
-            }
-        }
+        from_json_as(json_string, &ParseOptions::default(), "string", |value| match value {
+            Value::String(s) => Ok(s),
+            other => Err(other),
+        })
+    }
+}
+
+impl Default for StringParser {
+    fn default() -> StringParser {
+        StringParser::new()
     }
 }
 
 impl StringParser {
     pub fn new() -> StringParser {
+        StringParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> StringParser {
         StringParser{
-            buffer:     String::new(),
-            hex_string: String::new(),
-            state:      ParseState::SquareOne
+            options,
+            buffer:         String::new(),
+            hex_string:     String::new(),
+            high_surrogate: None,
+            state:          ParseState::SquareOne
+        }
+    }
+
+    /// Handles the code unit of a completed `\uXXXX` escape. A high surrogate is held back until
+    /// the low surrogate escape that must follow it arrives.
+    fn push_code_unit(&mut self, code_unit: u32) -> Result<(),ErrorKind> {
+        match (self.high_surrogate.take(), code_unit) {
+            (None, 0xd800..=0xdbff) => {
+                self.high_surrogate = Some(code_unit);
+                self.state          = ParseState::LowSurrogateEscapeExpected;
+                return Ok(());
+            },
+            (None, 0xdc00..=0xdfff) => {
+                return Err(ErrorKind::UnpairedSurrogate(code_unit));
+            },
+            (Some(high), 0xdc00..=0xdfff) => {
+                let code_point: u32 = 0x10000 + ((high - 0xd800) << 10) + (code_unit - 0xdc00);
+                self.push_code_point(code_point)?;
+            },
+            (Some(high), _) => {
+                return Err(ErrorKind::UnpairedSurrogate(high));
+            },
+            (None, _) => {
+                self.push_code_point(code_unit)?;
+            },
+        }
+        self.state = ParseState::ExpectingChars;
+        Ok(())
+    }
+
+    fn push_code_point(&mut self, code_point: u32) -> Result<(),ErrorKind> {
+        match char::from_u32(code_point) {
+            Some(hex_ch) => {
+                self.buffer.push(hex_ch);
+                Ok(())
+            },
+            None => {
+                Err(ErrorKind::InvalidUnicodeChar(code_point))
+            },
         }
     }
 }
 
 impl Parser for StringParser {
-    fn get_result(&self) -> Result<Value, ParseError> {
+    fn get_result(&self) -> Result<Value, ErrorKind> {
         match self.state {
-            ParseState::SquareOne            => { Err(ParseError::EmptyStringGiven) },
-            ParseState::ExpectingChars       => { Err(ParseError::UnterminatedToken('"')) },
-            ParseState::EscapeCharFound      => { Err(ParseError::UnterminatedToken('"')) },
-            ParseState::HexDigitExpected(_)  => { Err(ParseError::UnterminatedToken('"')) },
+            ParseState::SquareOne            => { Err(ErrorKind::EmptyInput) },
+            ParseState::ExpectingChars       => { Err(ErrorKind::UnterminatedToken('"')) },
+            ParseState::EscapeCharFound      => { Err(ErrorKind::UnterminatedToken('"')) },
+            ParseState::HexDigitExpected(_)  => { Err(ErrorKind::UnterminatedToken('"')) },
+            ParseState::LowSurrogateEscapeExpected
+            | ParseState::LowSurrogateUExpected => { Err(ErrorKind::UnterminatedToken('"')) },
             ParseState::ExpectingEndOfString => { Ok(Value::String(self.buffer.clone())) },
         }
     }
-    fn push_token(&mut self, ch: char) -> Result<(),ParseError> {
+    fn is_complete(&self) -> bool {
+        matches!(self.state, ParseState::ExpectingEndOfString)
+    }
+    fn has_result(&self) -> bool {
+        self.is_complete()
+    }
+    fn take_result(&mut self) -> Result<Value, ErrorKind> {
+        match self.state {
+            ParseState::ExpectingEndOfString => { Ok(Value::String(mem::take(&mut self.buffer))) },
+            _                                => { self.get_result() },
+        }
+    }
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
         match self.state {
             ParseState::SquareOne => {
                 match ch {
@@ -61,7 +122,7 @@ impl Parser for StringParser {
                         self.state = ParseState::ExpectingChars;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch))
+                        return Err(ErrorKind::UnexpectedToken(ch))
                     },
                 }
             },
@@ -73,7 +134,9 @@ impl Parser for StringParser {
                     '\\' => {
                         self.state = ParseState::EscapeCharFound;
                     },
-                    // TODO add the control characters that JSON does not allow here
+                    '\u{00}'..='\u{1f}' if !self.options.allow_control_chars => {
+                        return Err(ErrorKind::UnescapedControlChar(ch));
+                    },
                     _ => {
                         self.buffer.push(ch);
                     },
@@ -118,48 +181,64 @@ impl Parser for StringParser {
                         self.state = ParseState::HexDigitExpected(0);
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(ErrorKind::InvalidEscape(ch));
                     },
                 }
             },
-            ParseState::HexDigitExpected(ref mut n @ 0...2) => {
+            ParseState::HexDigitExpected(ref mut n @ 0..=2) => {
                 match ch {
-                    '0'...'9' | 'a'...'f' | 'A'...'F' => {
+                    '0'..='9' | 'a'..='f' | 'A'..='F' => {
                         self.hex_string.push(ch);
                         *n+=1; // effectively changes the state
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(ErrorKind::InvalidEscape(ch));
                     },
                 }
             },
             ParseState::HexDigitExpected(3) => {
                 match ch {
-                    '0'...'9' | 'a'...'f' | 'A'...'F' => {
+                    '0'..='9' | 'a'..='f' | 'A'..='F' => {
                         self.hex_string.push(ch);
                         let hex_string_int: u32 = u32::from_str_radix(&self.hex_string,16).unwrap();
-                        match char::from_u32(hex_string_int) {
-                            Some(hex_ch) => {
-                                self.buffer.push(hex_ch);
-                                self.state = ParseState::ExpectingChars;
-                            },
-                            None => {
-                                return Err(ParseError::InvalidUnicodeChar(hex_string_int));
-                            },
-                        }
+                        self.push_code_unit(hex_string_int)?;
                     },
                     _ => {
-                        return Err(ParseError::UnexpectedToken(ch));
+                        return Err(ErrorKind::InvalidEscape(ch));
                     },
                 }
             },
             ParseState::HexDigitExpected(_) => {
-                return Err(ParseError::UnexpectedToken(ch));
+                return Err(ErrorKind::UnexpectedToken(ch));
+            },
+            ParseState::LowSurrogateEscapeExpected => {
+                match ch {
+                    '\\' => {
+                        self.state = ParseState::LowSurrogateUExpected;
+                    },
+                    _ => {
+                        return Err(ErrorKind::UnpairedSurrogate(self.high_surrogate.unwrap_or(0)));
+                    },
+                }
+            },
+            ParseState::LowSurrogateUExpected => {
+                match ch {
+                    'u' => {
+                        self.hex_string = String::new();
+                        self.state = ParseState::HexDigitExpected(0);
+                    },
+                    _ => {
+                        return Err(ErrorKind::UnpairedSurrogate(self.high_surrogate.unwrap_or(0)));
+                    },
+                }
             },
             ParseState::ExpectingEndOfString => {
-                return Err(ParseError::UnexpectedToken(ch));
+                return Err(ErrorKind::UnexpectedToken(ch));
             },
         }
+        if self.buffer.len() > self.options.max_string_length {
+            return Err(ErrorKind::StringTooLong(self.options.max_string_length));
+        }
         Ok(())
     }
 }
@@ -181,6 +260,12 @@ fn parse_strings_with_escapes() {
     assert_eq!(&*String::from_json("\"\\u0041\"").unwrap(),"A");
 }
 
+#[test]
+fn parse_strings_with_surrogate_pairs() {
+    assert_eq!(&*String::from_json("\"\\ud83d\\ude00\"").unwrap(),"😀");
+    assert_eq!(&*String::from_json("\"a\\uD834\\uDD1Eb\"").unwrap(),"a\u{1d11e}b");
+}
+
 // SAD PATHS
 #[test]
 fn parse_unterminated_string_fails() {
@@ -192,7 +277,35 @@ fn parse_nothingness_fails() {
     assert!(String::from_json("").is_err());
 }
 
+#[test]
+fn parse_lone_or_mismatched_surrogates_fails() {
+    assert_eq!(String::from_json("\"\\ud83d\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xd83d));
+    assert_eq!(String::from_json("\"\\ud83dx\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xd83d));
+    assert_eq!(String::from_json("\"\\ud83d\\n\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xd83d));
+    assert_eq!(String::from_json("\"\\ud83d\\u0041\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xd83d));
+    assert_eq!(String::from_json("\"\\ud83d\\ud83d\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xd83d));
+    assert_eq!(String::from_json("\"\\ude00\"").unwrap_err().kind(), &ErrorKind::UnpairedSurrogate(0xde00));
+}
+
+#[test]
+fn parse_unescaped_control_chars_fails() {
+    assert_eq!(String::from_json("\"a\nb\"").unwrap_err().kind(), &ErrorKind::UnescapedControlChar('\n'));
+    assert_eq!(String::from_json("\"\u{00}\"").unwrap_err().kind(), &ErrorKind::UnescapedControlChar('\u{00}'));
+    assert_eq!(::parsing::parse("[\"\t\"]").unwrap_err().kind(), &ErrorKind::UnescapedControlChar('\t'));
+    assert_eq!(&*String::from_json("\"\u{7f}\"").unwrap(), "\u{7f}");
+}
+
+#[test]
+fn lenient_mode_accepts_control_chars() {
+    let options: ParseOptions = ParseOptions { allow_control_chars: true, ..ParseOptions::default() };
+    assert_eq!(::parsing::parse_with_options("\"a\nb\u{1f}\"", &options).unwrap(), Value::String("a\nb\u{1f}".to_string()));
+    assert_eq!(::parsing::parse_with_options("{\"\t\":[\"\r\"]}", &options).unwrap(),
+               ::parsing::parse("{\"\\t\":[\"\\r\"]}").unwrap());
+}
+
 #[test]
 fn parse_invalid_escape_sequence_fails() {
     assert!(String::from_json("\\h").is_err());
+    assert_eq!(String::from_json("\"\\h\"").unwrap_err().kind(), &ErrorKind::InvalidEscape('h'));
+    assert_eq!(String::from_json("\"\\u12g4\"").unwrap_err().kind(), &ErrorKind::InvalidEscape('g'));
 }
@@ -0,0 +1,170 @@
+use std::char;
+use std::io;
+use parsing::{ErrorKind};
+
+/// Turns bytes into characters one byte at a time, so input can arrive in pieces that split
+/// characters anywhere. Rejects everything RFC 3629 forbids: stray continuation bytes, overlong
+/// forms, surrogates and code points past U+10FFFF.
+#[derive(Default)]
+pub struct Utf8Decoder {
+    code_point: u32,
+    /// Continuation bytes still to come for the current character.
+    remaining:  u8,
+    /// The range the next continuation byte must fall in; narrower than usual straight after
+    /// some lead bytes.
+    low:        u8,
+    high:       u8,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Utf8Decoder {
+        Utf8Decoder::default()
+    }
+
+    /// Takes the next byte, handing back a character once its last byte has arrived.
+    pub fn push(&mut self, byte: u8) -> Result<Option<char>,ErrorKind> {
+        if self.remaining == 0 {
+            let (remaining, low, high): (u8, u8, u8) = match byte {
+                0x00..=0x7f => return Ok(Some(byte as char)),
+                0xc2..=0xdf => (1, 0x80, 0xbf),
+                0xe0        => (2, 0xa0, 0xbf),
+                0xed        => (2, 0x80, 0x9f),
+                0xe1..=0xef => (2, 0x80, 0xbf),
+                0xf0        => (3, 0x90, 0xbf),
+                0xf1..=0xf3 => (3, 0x80, 0xbf),
+                0xf4        => (3, 0x80, 0x8f),
+                _           => return Err(ErrorKind::InvalidUtf8(byte)),
+            };
+            self.code_point = byte as u32 & (0x3f >> remaining);
+            self.remaining  = remaining;
+            self.low        = low;
+            self.high       = high;
+            return Ok(None);
+        }
+        if byte < self.low || byte > self.high {
+            self.remaining = 0;
+            return Err(ErrorKind::InvalidUtf8(byte));
+        }
+        self.code_point = (self.code_point << 6) | (byte & 0x3f) as u32;
+        self.remaining -= 1;
+        self.low        = 0x80;
+        self.high       = 0xbf;
+        if self.remaining == 0 {
+            Ok(char::from_u32(self.code_point))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Checks that the input did not stop partway through a character.
+    pub fn finish(&self) -> Result<(),ErrorKind> {
+        if self.remaining == 0 {
+            Ok(())
+        } else {
+            Err(ErrorKind::UnexpectedEndOfInput)
+        }
+    }
+}
+
+/// The characters of a stream of bytes, ending at the first error.
+pub(crate) struct Utf8Chars<I: Iterator<Item = Result<u8,ErrorKind>>> {
+    bytes:   I,
+    decoder: Utf8Decoder,
+    failed:  bool,
+}
+
+impl<I: Iterator<Item = Result<u8,ErrorKind>>> Utf8Chars<I> {
+    pub(crate) fn new(bytes: I) -> Utf8Chars<I> {
+        Utf8Chars { bytes, decoder: Utf8Decoder::new(), failed: false }
+    }
+}
+
+impl<I: Iterator<Item = Result<u8,ErrorKind>>> Iterator for Utf8Chars<I> {
+    type Item = Result<char,ErrorKind>;
+
+    fn next(&mut self) -> Option<Result<char,ErrorKind>> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let decoded: Result<Option<char>,ErrorKind> = match self.bytes.next() {
+                Some(Ok(byte)) => self.decoder.push(byte),
+                Some(Err(e))   => Err(e),
+                None           => self.decoder.finish().map(|_| None),
+            };
+            match decoded {
+                Ok(Some(ch)) => return Some(Ok(ch)),
+                Ok(None) if self.decoder.remaining > 0 => continue,
+                Ok(None)     => return None,
+                Err(e)       => {
+                    self.failed = true;
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
+}
+
+/// Size of the chunks read from an `io::Read` at a time.
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The bytes of an `io::Read`, read a buffer at a time.
+pub(crate) struct ReadBytes<R: io::Read> {
+    reader: R,
+    buffer: Box<[u8]>,
+    start:  usize,
+    end:    usize,
+}
+
+impl<R: io::Read> ReadBytes<R> {
+    pub(crate) fn new(reader: R) -> ReadBytes<R> {
+        ReadBytes {
+            reader,
+            buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            start:  0,
+            end:    0,
+        }
+    }
+}
+
+impl<R: io::Read> Iterator for ReadBytes<R> {
+    type Item = Result<u8,ErrorKind>;
+
+    fn next(&mut self) -> Option<Result<u8,ErrorKind>> {
+        while self.start == self.end {
+            match self.reader.read(&mut self.buffer) {
+                Ok(0)  => return None,
+                Ok(n)  => {
+                    self.start = 0;
+                    self.end   = n;
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(ErrorKind::Io(e.kind()))),
+            }
+        }
+        self.start += 1;
+        Some(Ok(self.buffer[self.start - 1]))
+    }
+}
+
+#[cfg(test)]
+fn decode(bytes: &[u8]) -> Result<String,ErrorKind> {
+    Utf8Chars::new(bytes.iter().map(|&b| Ok(b))).collect()
+}
+
+#[test]
+fn valid_utf8_decodes() {
+    assert_eq!( decode("aé€😀\u{10ffff}".as_bytes()), Ok("aé€😀\u{10ffff}".to_string()) );
+    assert_eq!( decode(b""), Ok(String::new()) );
+}
+
+#[test]
+fn invalid_utf8_fails() {
+    assert_eq!( decode(b"a\x80"), Err(ErrorKind::InvalidUtf8(0x80)) );
+    assert_eq!( decode(b"\xc0\xaf"), Err(ErrorKind::InvalidUtf8(0xc0)) );
+    assert_eq!( decode(b"\xe0\x80\xaf"), Err(ErrorKind::InvalidUtf8(0x80)) );
+    assert_eq!( decode(b"\xed\xa0\x80"), Err(ErrorKind::InvalidUtf8(0xa0)) );
+    assert_eq!( decode(b"\xf4\x90\x80\x80"), Err(ErrorKind::InvalidUtf8(0x90)) );
+    assert_eq!( decode(b"\xe2\x82"), Err(ErrorKind::UnexpectedEndOfInput) );
+    assert_eq!( decode(b"\xe2(\xac"), Err(ErrorKind::InvalidUtf8(b'(')) );
+}
@@ -0,0 +1,290 @@
+use std::mem;
+use parsing::{Parser, ErrorKind, ParseOptions, is_whitespace, push_to_sub_parser};
+use parsing::string::{StringParser};
+use parsing::number::{NumberParser};
+use parsing::literal::{LiteralParser};
+use parsing::nil::{NilParser};
+use types::{Array, Object, Value};
+
+enum ParseState {
+    SquareOne,
+    ExpectingValue,
+    ExpectingValueOrEndOfArray,
+    ExpectingKeyOrEndOfObject,
+    ExpectingKey,
+    ParsingKey,
+    ExpectingColon,
+    ParsingScalar,
+    ExpectingCommaOrEnd,
+    ValueFinished,
+    /// The finished value has been handed over.
+    Spent,
+}
+
+/// An object or array that has been opened but not yet closed.
+enum Container {
+    /// The entries so far, and the key waiting for its value.
+    Object(Object, Option<String>),
+    Array(Array),
+}
+
+/// Parses any JSON value, picking the parser for it from the first significant character.
+/// Whitespace before and after the value is consumed; any other trailing character is rejected.
+///
+/// Open objects and arrays are kept on an explicit stack and only strings, numbers and literals
+/// get a parser of their own, so each character costs the same however deeply it is nested.
+pub struct ValueParser {
+    options: ParseOptions,
+    state:   ParseState,
+    stack:   Vec<Container>,
+    /// The parser for the string, number, literal or key being read.
+    scalar:  Box<dyn Parser>,
+    /// The value, once it is finished.
+    value:   Value,
+}
+
+impl Default for ValueParser {
+    fn default() -> ValueParser {
+        ValueParser::new()
+    }
+}
+
+impl ValueParser {
+    pub fn new() -> ValueParser {
+        ValueParser::with_options(ParseOptions::default())
+    }
+    pub fn with_options(options: ParseOptions) -> ValueParser {
+        ValueParser {
+            options,
+            state:  ParseState::SquareOne,
+            stack:  Vec::new(),
+            scalar: Box::new(NilParser::new()),
+            value:  Value::None,
+        }
+    }
+
+    /// Starts the value opening with `ch`, refusing to open an object or array nested deeper than
+    /// the options allow.
+    fn start_value(&mut self, ch: char) -> Result<(),ErrorKind> {
+        if let Some(Container::Array(array)) = self.stack.last() {
+            if array.len() >= self.options.max_array_elements {
+                return Err(ErrorKind::TooManyArrayElements(self.options.max_array_elements));
+            }
+        }
+        if (ch == '{' || ch == '[') && self.stack.len() >= self.options.max_depth {
+            return Err(ErrorKind::DepthLimitExceeded(self.options.max_depth));
+        }
+        match ch {
+            '{' => {
+                self.stack.push(Container::Object(Object::new(), None));
+                self.state = ParseState::ExpectingKeyOrEndOfObject;
+                Ok(())
+            },
+            '[' => {
+                self.stack.push(Container::Array(Array::new()));
+                self.state = ParseState::ExpectingValueOrEndOfArray;
+                Ok(())
+            },
+            _ => {
+                self.scalar = scalar_parser_for(ch, self.options)?;
+                self.state  = ParseState::ParsingScalar;
+                self.scalar.push_token(ch)
+            },
+        }
+    }
+
+    fn start_key(&mut self, ch: char) -> Result<(),ErrorKind> {
+        if let Some(Container::Object(object, _)) = self.stack.last() {
+            if object.len() >= self.options.max_object_keys {
+                return Err(ErrorKind::TooManyObjectKeys(self.options.max_object_keys));
+            }
+        }
+        match ch {
+            '"' => {
+                self.scalar = Box::new(StringParser::with_options(self.options));
+                self.state  = ParseState::ParsingKey;
+                self.scalar.push_token(ch)
+            },
+            _ => {
+                Err(ErrorKind::UnexpectedToken(ch))
+            },
+        }
+    }
+
+    /// Puts a finished value into the container it belongs to, or finishes the whole value.
+    fn value_finished(&mut self, value: Value) {
+        match self.stack.last_mut() {
+            Some(Container::Object(object, key)) => {
+                if let Some(key) = key.take() {
+                    object.insert(key, value);
+                }
+            },
+            Some(Container::Array(array)) => {
+                array.push(value);
+            },
+            None => {
+                self.value = value;
+                self.state = ParseState::ValueFinished;
+                return;
+            },
+        }
+        self.state = ParseState::ExpectingCommaOrEnd;
+    }
+
+    fn close(&mut self) {
+        let value: Value = match self.stack.pop() {
+            Some(Container::Object(object, _)) => Value::Object(object),
+            Some(Container::Array(array))      => Value::Array(array),
+            None                               => return,
+        };
+        self.value_finished(value);
+    }
+
+    /// What an unfinished value reports: the outermost open container, or else the scalar.
+    fn unfinished(&self) -> Result<Value, ErrorKind> {
+        match self.stack.first() {
+            Some(Container::Object(..)) => Err(ErrorKind::UnterminatedToken('{')),
+            Some(Container::Array(_))   => Err(ErrorKind::UnterminatedToken('[')),
+            None                        => self.scalar.get_result(),
+        }
+    }
+}
+
+/// Picks the parser for a string, number or literal starting with `ch`.
+fn scalar_parser_for(ch: char, options: ParseOptions) -> Result<Box<dyn Parser>,ErrorKind> {
+    match ch {
+        '"'              => Ok(Box::new(StringParser::with_options(options))),
+        '-' | '0'..='9'  => Ok(Box::new(NumberParser::with_options(options))),
+        't'              => Ok(Box::new(LiteralParser::new_true())),
+        'f'              => Ok(Box::new(LiteralParser::new_false())),
+        'n'              => Ok(Box::new(LiteralParser::new_null())),
+        _                => Err(ErrorKind::UnexpectedToken(ch)),
+    }
+}
+
+impl Parser for ValueParser {
+    fn get_result(&self) -> Result<Value, ErrorKind> {
+        match self.state {
+            ParseState::SquareOne     => { Err(ErrorKind::EmptyInput) },
+            ParseState::ValueFinished => { Ok(self.value.clone()) },
+            ParseState::Spent         => { Err(ErrorKind::UnexpectedEndOfInput) },
+            _                         => { self.unfinished() },
+        }
+    }
+    fn is_complete(&self) -> bool {
+        match self.state {
+            ParseState::ParsingScalar => self.stack.is_empty() && self.scalar.is_complete(),
+            ParseState::ValueFinished => true,
+            _                         => false,
+        }
+    }
+    fn has_result(&self) -> bool {
+        match self.state {
+            ParseState::ParsingScalar => self.stack.is_empty() && self.scalar.has_result(),
+            ParseState::ValueFinished => true,
+            _                         => false,
+        }
+    }
+    fn take_result(&mut self) -> Result<Value, ErrorKind> {
+        let result: Result<Value, ErrorKind> = match self.state {
+            ParseState::ValueFinished                          => { Ok(mem::replace(&mut self.value, Value::None)) },
+            ParseState::ParsingScalar if self.stack.is_empty() => { self.scalar.take_result() },
+            _                                                  => { return self.get_result() },
+        };
+        if result.is_ok() {
+            self.state = ParseState::Spent;
+        }
+        result
+    }
+    fn push_token(&mut self, ch: char) -> Result<(),ErrorKind> {
+        match self.state {
+            ParseState::ParsingKey => {
+                if let Some(key) = push_to_sub_parser(&mut self.scalar, ch)? {
+                    if let (Some(Container::Object(_, current)), Value::String(key)) = (self.stack.last_mut(), key) {
+                        *current = Some(key);
+                    }
+                    self.state = ParseState::ExpectingColon;
+                    return self.push_token(ch);
+                }
+            },
+            ParseState::ParsingScalar => {
+                if let Some(value) = push_to_sub_parser(&mut self.scalar, ch)? {
+                    self.value_finished(value);
+                    return self.push_token(ch);
+                }
+            },
+            _ if is_whitespace(ch) => {},
+            ParseState::SquareOne
+            | ParseState::ExpectingValue => {
+                self.start_value(ch)?;
+            },
+            ParseState::ExpectingValueOrEndOfArray => {
+                match ch {
+                    ']' => self.close(),
+                    _   => self.start_value(ch)?,
+                }
+            },
+            ParseState::ExpectingKeyOrEndOfObject => {
+                match ch {
+                    '}' => self.close(),
+                    _   => self.start_key(ch)?,
+                }
+            },
+            ParseState::ExpectingKey => {
+                self.start_key(ch)?;
+            },
+            ParseState::ExpectingColon => {
+                match ch {
+                    ':' => self.state = ParseState::ExpectingValue,
+                    _   => return Err(ErrorKind::UnexpectedToken(ch)),
+                }
+            },
+            ParseState::ExpectingCommaOrEnd => {
+                match (ch, self.stack.last()) {
+                    (',', Some(Container::Object(..))) => self.state = ParseState::ExpectingKey,
+                    (',', _)                           => self.state = ParseState::ExpectingValue,
+                    ('}', Some(Container::Object(..)))
+                    | (']', Some(Container::Array(_))) => self.close(),
+                    _                                  => return Err(ErrorKind::UnexpectedToken(ch)),
+                }
+            },
+            ParseState::ValueFinished
+            | ParseState::Spent => {
+                return Err(ErrorKind::UnexpectedToken(ch));
+            },
+        }
+        Ok(())
+    }
+}
+
+// HAPPY PATHS
+#[test]
+fn parse_scalars() {
+    assert_eq!(::parsing::parse("\"foo\"").unwrap(), Value::String("foo".to_string()));
+    assert_eq!(::parsing::parse("-1.5e1").unwrap(), Value::Number(::types::Number::from(-15.0)));
+}
+
+#[test]
+fn parse_skips_surrounding_whitespace() {
+    assert_eq!(::parsing::parse(" \t\r\n\"foo\"\n").unwrap(), Value::String("foo".to_string()));
+    assert_eq!(::parsing::parse(" 12 ").unwrap(), Value::Number(::types::Number::from(12)));
+}
+
+// SAD PATHS
+#[test]
+fn parse_nothingness_fails() {
+    assert_eq!(::parsing::parse("").unwrap_err().kind(), &ErrorKind::EmptyInput);
+    assert_eq!(::parsing::parse("   ").unwrap_err().kind(), &ErrorKind::EmptyInput);
+}
+
+#[test]
+fn parse_trailing_garbage_fails() {
+    assert_eq!(::parsing::parse("\"foo\" x").unwrap_err().kind(), &ErrorKind::TrailingCharacters('x'));
+    assert_eq!(::parsing::parse("12a").unwrap_err().kind(), &ErrorKind::TrailingCharacters('a'));
+    assert_eq!(::parsing::parse("1 2").unwrap_err().kind(), &ErrorKind::TrailingCharacters('2'));
+}
+
+#[test]
+fn parse_unknown_leading_token_fails() {
+    assert_eq!(::parsing::parse("x").unwrap_err().kind(), &ErrorKind::UnexpectedToken('x'));
+}
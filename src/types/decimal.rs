@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// A number kept exactly as written, for values such as money that must not pass through binary
+/// floating point. Its value is `digits × 10^exponent`, negated if `is_negative`.
+///
+/// Decimals are equal when their values are, so `0.10 == 0.1` even though each keeps its own text.
+#[derive(Clone, Debug)]
+pub struct Decimal {
+    literal:  String,
+    negative: bool,
+    digits:   String,
+    exponent: i64,
+}
+
+impl Decimal {
+    /// Splits the text of a valid JSON number into its parts. Fails only when the exponent does
+    /// not fit in an `i64`.
+    pub(crate) fn from_literal(literal: String) -> Option<Decimal> {
+        let negative: bool = literal.starts_with('-');
+        let unsigned: &str = literal.trim_start_matches('-');
+        let (significand, exponent): (&str, i64) = match unsigned.find(['e', 'E']) {
+            Some(i) => (&unsigned[..i], unsigned[i + 1..].trim_start_matches('+').parse::<i64>().ok()?),
+            None    => (unsigned, 0),
+        };
+        let (integer, fraction): (&str, &str) = match significand.find('.') {
+            Some(i) => (&significand[..i], &significand[i + 1..]),
+            None    => (significand, ""),
+        };
+        let mut digits: String = format!("{}{}", integer, fraction).trim_start_matches('0').to_string();
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        Some(Decimal {
+            negative,
+            digits,
+            exponent: exponent.checked_sub(fraction.len() as i64)?,
+            literal,
+        })
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+    /// The decimal digits of the coefficient, without sign or leading zeros. Trailing zeros are
+    /// kept, so `1.50` has digits `150`.
+    pub fn digits(&self) -> &str {
+        &self.digits
+    }
+    pub fn exponent(&self) -> i64 {
+        self.exponent
+    }
+    /// The signed coefficient, when it fits in an `i128`.
+    pub fn mantissa(&self) -> Option<i128> {
+        let magnitude: i128 = self.digits.parse::<i128>().ok()?;
+        Some(if self.negative { -magnitude } else { magnitude })
+    }
+    /// The number exactly as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.literal
+    }
+
+    /// The sign, digits and exponent with trailing zeros moved into the exponent, which two
+    /// decimals share exactly when they have the same value.
+    pub(crate) fn normalized(&self) -> (bool, &str, i64) {
+        let digits: &str = self.digits.trim_end_matches('0');
+        if digits.is_empty() {
+            return (false, "0", 0);
+        }
+        (self.negative, digits, self.exponent.saturating_add((self.digits.len() - digits.len()) as i64))
+    }
+
+    /// The sign and magnitude, when the value is a whole number whose magnitude fits in a `u64`.
+    pub(crate) fn as_integer(&self) -> Option<(bool, u64)> {
+        let (negative, digits, exponent): (bool, &str, i64) = self.normalized();
+        if !(0..=19).contains(&exponent) {
+            return None;
+        }
+        let magnitude: u64 = digits.parse::<u64>().ok()?;
+        Some((negative, magnitude.checked_mul(10u64.checked_pow(exponent as u32)?)?))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.literal)
+    }
+}
+
+#[test]
+fn decimals_split_into_mantissa_and_exponent() {
+    let price: Decimal = Decimal::from_literal("-12.340".to_string()).unwrap();
+    assert!( price.is_negative() );
+    assert_eq!( price.digits(), "12340" );
+    assert_eq!( price.mantissa(), Some(-12340) );
+    assert_eq!( price.exponent(), -3 );
+    assert_eq!( price.to_string(), "-12.340" );
+
+    let tiny: Decimal = Decimal::from_literal("0.01E+5".to_string()).unwrap();
+    assert_eq!( (tiny.mantissa(), tiny.exponent()), (Some(1), 3) );
+    assert_eq!( Decimal::from_literal("0".to_string()).unwrap().digits(), "0" );
+}
+
+#[test]
+fn decimals_with_huge_coefficients_or_exponents() {
+    let long: Decimal = Decimal::from_literal("1234567890123456789012345678901234567890.5".to_string()).unwrap();
+    assert_eq!( long.mantissa(), None );
+    assert_eq!( long.exponent(), -1 );
+    assert!( Decimal::from_literal("1e99999999999999999999".to_string()).is_none() );
+}
+
+#[test]
+fn decimals_compare_by_value() {
+    let decimal = |literal: &str| Decimal::from_literal(literal.to_string()).unwrap();
+    assert_eq!( decimal("0.10"), decimal("0.1") );
+    assert_eq!( decimal("1e2"), decimal("100.00") );
+    assert_eq!( decimal("-0"), decimal("0.0") );
+    assert_ne!( decimal("0.1"), decimal("-0.1") );
+    assert_ne!( decimal("1"), decimal("10") );
+}
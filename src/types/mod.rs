@@ -1,9 +1,13 @@
-use std::collections::{HashMap};
+pub mod number;
+pub mod decimal;
+pub mod object;
 
-pub type Number = f64;
+pub use self::number::{Number};
+pub use self::decimal::{Decimal};
+pub use self::object::{Object};
 pub type Array  = Vec<Value>;
-pub type Object = HashMap<String,Value>;
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Array(Array),
     Boolean(bool),
@@ -12,3 +16,17 @@ pub enum Value {
     Object(Object),
     String(String),
 }
+
+impl Value {
+    /// The name JSON gives to the kind of this value.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Value::Array(_)   => "array",
+            Value::Boolean(_) => "boolean",
+            Value::None       => "null",
+            Value::Number(_)  => "number",
+            Value::Object(_)  => "object",
+            Value::String(_)  => "string",
+        }
+    }
+}
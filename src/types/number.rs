@@ -0,0 +1,205 @@
+use std::fmt;
+use types::decimal::{Decimal};
+
+/// A JSON number. Integers are kept as `u64` or `i64` when they fit, so they survive a round trip
+/// exactly; everything else is an `f64` unless the parser was asked to keep it verbatim or as a
+/// `Decimal`. Literals too large for an `f64` are always kept verbatim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Number(N);
+
+#[derive(Clone, Debug, PartialEq)]
+enum N {
+    PosInt(u64),
+    /// Always less than zero.
+    NegInt(i64),
+    Float(f64),
+    /// The original text of a number that no primitive holds exactly.
+    Verbatim(String),
+    Decimal(Decimal),
+}
+
+impl Number {
+    /// Wraps the text of a valid JSON number that should be written back out exactly as given.
+    pub(crate) fn verbatim(literal: String) -> Number {
+        Number(N::Verbatim(literal))
+    }
+
+    pub fn is_u64(&self) -> bool {
+        matches!(self.0, N::PosInt(_))
+    }
+    pub fn is_i64(&self) -> bool {
+        match self.0 {
+            N::PosInt(n) => n <= i64::MAX as u64,
+            N::NegInt(_) => true,
+            _            => false,
+        }
+    }
+    pub fn is_f64(&self) -> bool {
+        matches!(self.0, N::Float(_))
+    }
+    pub fn is_decimal(&self) -> bool {
+        matches!(self.0, N::Decimal(_))
+    }
+    /// Whether the number can be written as JSON, i.e. is not NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        match self.0 {
+            N::Float(n) => n.is_finite(),
+            _           => true,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.0 {
+            N::PosInt(n)      => Some(n),
+            N::Decimal(ref d) => match d.as_integer() {
+                Some((false, n)) => Some(n),
+                _                => None,
+            },
+            _                 => None,
+        }
+    }
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.0 {
+            N::PosInt(n) if n <= i64::MAX as u64 => Some(n as i64),
+            N::NegInt(n)      => Some(n),
+            N::Decimal(ref d) => match d.as_integer() {
+                Some((false, n)) if n <= i64::MAX as u64 => Some(n as i64),
+                Some((true, n)) if n <= 1 << 63          => Some((n as i64).wrapping_neg()),
+                _                                        => None,
+            },
+            _                 => None,
+        }
+    }
+    /// The nearest `f64`, which loses precision for integers beyond 2^53.
+    pub fn as_f64(&self) -> f64 {
+        match self.0 {
+            N::PosInt(n)          => n as f64,
+            N::NegInt(n)          => n as f64,
+            N::Float(n)           => n,
+            N::Verbatim(ref text) => text.parse::<f64>().unwrap_or(f64::NAN),
+            N::Decimal(ref d)     => d.as_str().parse::<f64>().unwrap_or(f64::NAN),
+        }
+    }
+    pub fn as_decimal(&self) -> Option<&Decimal> {
+        match self.0 {
+            N::Decimal(ref d) => Some(d),
+            _                 => None,
+        }
+    }
+    /// The original text of a number that was kept verbatim or as a `Decimal`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.0 {
+            N::Verbatim(ref text) => Some(text),
+            N::Decimal(ref d)     => Some(d.as_str()),
+            _                     => None,
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            N::PosInt(n)          => write!(f, "{}", n),
+            N::NegInt(n)          => write!(f, "{}", n),
+            N::Float(n)           => f.write_str(&shortest_float(n)),
+            N::Verbatim(ref text) => f.write_str(text),
+            N::Decimal(ref d)     => f.write_str(d.as_str()),
+        }
+    }
+}
+
+/// Rust prints the fewest digits that read back as the same `f64`, but `Display` never uses an
+/// exponent and `LowerExp` always does, so pick whichever of the two comes out shorter.
+fn shortest_float(n: f64) -> String {
+    let plain: String       = format!("{}", n);
+    let exponential: String = format!("{:e}", n);
+    if exponential.len() < plain.len() {
+        exponential
+    } else {
+        plain
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Number {
+            fn from(n: $t) -> Number {
+                Number(N::PosInt(n as u64))
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Number {
+            fn from(n: $t) -> Number {
+                if n < 0 {
+                    Number(N::NegInt(n as i64))
+                } else {
+                    Number(N::PosInt(n as u64))
+                }
+            }
+        }
+    )*};
+}
+
+from_unsigned!(u8 u16 u32 u64 usize);
+from_signed!(i8 i16 i32 i64 isize);
+
+impl From<f32> for Number {
+    fn from(n: f32) -> Number {
+        Number(N::Float(n as f64))
+    }
+}
+
+impl From<f64> for Number {
+    fn from(n: f64) -> Number {
+        Number(N::Float(n))
+    }
+}
+
+impl From<Decimal> for Number {
+    fn from(d: Decimal) -> Number {
+        Number(N::Decimal(d))
+    }
+}
+
+#[test]
+fn integers_convert_without_loss() {
+    assert_eq!( Number::from(u64::MAX).as_u64(), Some(u64::MAX) );
+    assert_eq!( Number::from(u64::MAX).as_i64(), None );
+    assert_eq!( Number::from(i64::MIN).as_i64(), Some(i64::MIN) );
+    assert_eq!( Number::from(i64::MIN).as_u64(), None );
+    assert_eq!( Number::from(7i8), Number::from(7u64) );
+    assert!( Number::from(-1).is_i64() && !Number::from(-1).is_u64() );
+}
+
+#[test]
+fn whole_decimals_convert_to_integers() {
+    let decimal = |literal: &str| Number::from(Decimal::from_literal(literal.to_string()).unwrap());
+    assert_eq!( decimal("1e2").as_u64(), Some(100) );
+    assert_eq!( decimal("100.0").as_i64(), Some(100) );
+    assert_eq!( decimal("-0.5e1").as_i64(), Some(-5) );
+    assert_eq!( decimal("-5").as_u64(), None );
+    assert_eq!( decimal("-9223372036854775808").as_i64(), Some(i64::MIN) );
+    assert_eq!( decimal("18446744073709551615").as_u64(), Some(u64::MAX) );
+    assert_eq!( decimal("1.8446744073709551616e19").as_u64(), None );
+    assert_eq!( decimal("1.5").as_u64(), None );
+    assert_eq!( decimal("1e99999").as_u64(), None );
+}
+
+#[test]
+fn numbers_display_as_json() {
+    assert_eq!( Number::from(9007199254740993u64).to_string(), "9007199254740993" );
+    assert_eq!( Number::from(-12).to_string(), "-12" );
+    assert_eq!( Number::from(1.5).to_string(), "1.5" );
+    assert_eq!( Number::from(1e300).to_string(), "1e300" );
+    assert_eq!( Number::from(-2.5e-8).to_string(), "-2.5e-8" );
+    assert_eq!( Number::from(123456.789).to_string(), "123456.789" );
+    assert_eq!( Number::from(1e21).to_string(), "1e21" );
+    assert_eq!( Number::from(100.0).to_string(), "100" );
+    assert_eq!( Number::from(-0.0).to_string(), "-0" );
+    assert_eq!( Number::verbatim("123456789012345678901234567890".to_string()).to_string(), "123456789012345678901234567890" );
+    assert_eq!( Number::from(Decimal::from_literal("0.10".to_string()).unwrap()).to_string(), "0.10" );
+}
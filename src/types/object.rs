@@ -0,0 +1,211 @@
+use std::collections::{HashMap};
+use std::iter::{FromIterator};
+use std::ops::{Index};
+use std::slice;
+use std::vec;
+use types::{Value};
+
+/// A JSON object. Entries keep the order they were inserted in, which for parsed objects is the
+/// order they appear in the document, so encoding is deterministic. Comparison ignores order,
+/// like JSON itself.
+///
+/// Every key is held twice, once beside its value and once in the index used for lookups, so an
+/// object costs about one extra copy of its keys over a plain map.
+#[derive(Clone, Debug, Default)]
+pub struct Object {
+    entries: Vec<(String,Value)>,
+    indices: HashMap<String,usize>,
+}
+
+impl Object {
+    pub fn new() -> Object {
+        Object::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts `value` under `key`. A key that is already present keeps its place and has its
+    /// value replaced, which is handed back.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        match self.indices.get(&key) {
+            Some(&i) => {
+                Some(::std::mem::replace(&mut self.entries[i].1, value))
+            },
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            },
+        }
+    }
+
+    /// Removes `key`, keeping the order of the remaining entries.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let i: usize = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+        for (k, _) in &self.entries[i..] {
+            *self.indices.get_mut(k).unwrap() -= 1;
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.indices.get(key).map(|&i| &self.entries[i].1)
+    }
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self.indices.get(key) {
+            Some(&i) => Some(&mut self.entries[i].1),
+            None     => None,
+        }
+    }
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { entries: self.entries.iter() }
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|entry| &entry.0)
+    }
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|entry| &entry.1)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut { entries: self.entries.iter_mut() }
+    }
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.entries.iter_mut().map(|entry| &mut entry.1)
+    }
+}
+
+impl PartialEq for Object {
+    fn eq(&self, other: &Object) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Index<&str> for Object {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+/// The entries of an `Object` in order.
+pub struct Iter<'a> {
+    entries: slice::Iter<'a,(String,Value)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Value);
+
+    fn next(&mut self) -> Option<(&'a String, &'a Value)> {
+        self.entries.next().map(|entry| (&entry.0, &entry.1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+/// The entries of an `Object` in order, with their values open to change.
+pub struct IterMut<'a> {
+    entries: slice::IterMut<'a,(String,Value)>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Value);
+
+    fn next(&mut self) -> Option<(&'a String, &'a mut Value)> {
+        self.entries.next().map(|entry| (&entry.0, &mut entry.1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a> IntoIterator for &'a Object {
+    type Item     = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Object {
+    type Item     = (&'a String, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for Object {
+    type Item     = (String, Value);
+    type IntoIter = vec::IntoIter<(String,Value)>;
+
+    fn into_iter(self) -> vec::IntoIter<(String,Value)> {
+        self.entries.into_iter()
+    }
+}
+
+impl FromIterator<(String,Value)> for Object {
+    fn from_iter<I: IntoIterator<Item = (String,Value)>>(iter: I) -> Object {
+        let mut object: Object = Object::new();
+        for (key, value) in iter {
+            object.insert(key, value);
+        }
+        object
+    }
+}
+
+#[test]
+fn entries_keep_insertion_order() {
+    let mut object: Object = Object::new();
+    for key in ["z", "a", "m"].iter() {
+        object.insert(key.to_string(), Value::None);
+    }
+    object.insert("a".to_string(), Value::Boolean(true));
+    assert_eq!( object.keys().map(|k| k.as_str()).collect::<Vec<&str>>(), vec!["z", "a", "m"] );
+    assert_eq!( object["a"], Value::Boolean(true) );
+
+    assert_eq!( object.remove("z"), Some(Value::None) );
+    assert_eq!( object.remove("z"), None );
+    object.insert("b".to_string(), Value::None);
+    assert_eq!( object.keys().map(|k| k.as_str()).collect::<Vec<&str>>(), vec!["a", "m", "b"] );
+    assert_eq!( object.get("m"), Some(&Value::None) );
+    assert_eq!( object.len(), 3 );
+}
+
+#[test]
+fn values_can_be_changed_in_place() {
+    let mut object: Object = vec![("a".to_string(), Value::None), ("b".to_string(), Value::None)].into_iter().collect();
+    for (key, value) in object.iter_mut() {
+        *value = Value::String(key.clone());
+    }
+    assert_eq!( object["b"], Value::String("b".to_string()) );
+    for value in object.values_mut() {
+        *value = Value::Boolean(true);
+    }
+    for (_, value) in &mut object {
+        assert_eq!( *value, Value::Boolean(true) );
+    }
+    assert_eq!( object.keys().map(|k| k.as_str()).collect::<Vec<&str>>(), vec!["a", "b"] );
+}
+
+#[test]
+fn comparison_ignores_order() {
+    let forwards: Object = vec![("a".to_string(), Value::None), ("b".to_string(), Value::None)].into_iter().collect();
+    let backwards: Object = vec![("b".to_string(), Value::None), ("a".to_string(), Value::None)].into_iter().collect();
+    assert_eq!( forwards, backwards );
+    assert!( forwards != Object::new() );
+}